@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use ark_crypto_primitives::crh::{
+    CRHScheme, CRHSchemeGadget,
+    poseidon::{CRH as PoseidonCRH, constraints::CRHGadget as PoseidonCRHGadget},
+};
+use ark_ff::UniformRand;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::rand::Rng;
+
+use crate::{
+    F,
+    data_structures::{
+        credential::{Credential, CredentialVar},
+        hasher::{CredentialHasher, PoseidonHasher},
+    },
+    error::Error,
+};
+
+// challenge binding 해시의 domain tag. Holder 공개 키, nullifier, 서명 해시와 값이 겹치지 않게 함
+const CHALLENGE_DOMAIN: u64 = 5;
+
+/// Holder 비밀 키, credential의 Poseidon 해시와 Verifier가 발급한 challenge의 Poseidon 해시
+/// circuit이 public input으로 공개하므로 증명은 challenge를 발급받은 증명 요청에서만 유효
+/// 비밀 키 없이는 계산할 수 없으므로 Issuer / Verifier가 publish된 credential 해시로 Holder를 찾을 수 없음
+pub fn challenge_binding(holder_secret_key: F, credential: &Credential, challenge: F) -> F {
+    PoseidonCRH::<F>::evaluate(
+        PoseidonHasher::leaf_params(),
+        [
+            F::from(CHALLENGE_DOMAIN),
+            holder_secret_key,
+            PoseidonHasher::hash(credential),
+            challenge,
+        ],
+    )
+    .expect("poseidon accepts any number of field elements")
+}
+
+/// circuit 안에서 `challenge_binding`과 동일하게 계산
+pub fn challenge_binding_var(
+    cs: ConstraintSystemRef<F>,
+    holder_secret_key: &FpVar<F>,
+    credential: &CredentialVar,
+    challenge: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    let params_var = PoseidonHasher::leaf_params_var(cs)?;
+    let credential_hash_var = PoseidonHasher::hash_var(&params_var, credential)?;
+    PoseidonCRHGadget::<F>::evaluate(
+        &params_var,
+        &[
+            FpVar::Constant(F::from(CHALLENGE_DOMAIN)),
+            holder_secret_key.clone(),
+            credential_hash_var,
+            challenge.clone(),
+        ],
+    )
+}
+
+/// Verifier가 발급했지만 아직 증명을 받지 않은 challenge 목록
+#[derive(Clone, Debug, Default)]
+pub struct ChallengeStore {
+    pending: HashSet<F>,
+}
+
+impl ChallengeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 새 challenge 발급
+    pub fn issue<R: Rng>(&mut self, rng: &mut R) -> F {
+        let challenge = F::rand(rng);
+        self.pending.insert(challenge);
+        challenge
+    }
+
+    pub fn is_pending(&self, challenge: &F) -> bool {
+        self.pending.contains(challenge)
+    }
+
+    // challenge는 한 번만 사용. 발급하지 않았거나 이미 사용한 challenge면 오류
+    pub fn consume(&mut self, challenge: F) -> Result<(), Error> {
+        if !self.pending.remove(&challenge) {
+            return Err(Error::UnknownChallenge);
+        }
+        Ok(())
+    }
+}
@@ -1,86 +1,571 @@
 use crate::{
-    F, MAX_CREDENTIALS, Sha256Digest, data_structures::credential::Credential, utils::utils::*,
+    F,
+    data_structures::{
+        challenge,
+        credential::{Credential, CredentialEncoding, CredentialVar},
+        date::Date,
+        diagnostics::{ConstraintSections, Section, SectionedCircuit, section},
+        disclosure::Disclosure,
+        hasher::{CredentialHasher, PoseidonHasher},
+        holder_key::public_key_var,
+        issuer_set::{IssuerPath, IssuerPathVar, issuer_id_field},
+        merkle_tree::{CredentialPath, CredentialPathVar},
+        nullifier,
+        policy::{Policy, Submitted},
+        predicate::Predicate,
+        revocation::{RevocationPath, RevocationPathVar, not_revoked_leaf},
+        signature::{CredentialSignature, CredentialSignatureVar},
+    },
+    error::Error,
+    utils::utils::*,
 };
 
-use ark_crypto_primitives::crh::sha256::constraints::{DigestVar, Sha256Gadget};
+use ark_ed_on_bn254::{EdwardsAffine, constraints::EdwardsVar};
 use ark_r1cs_std::{
-    ToBytesGadget, alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, prelude::Boolean,
+    alloc::{AllocVar, AllocationMode},
+    eq::EqGadget,
+    fields::fp::FpVar,
+    prelude::Boolean,
 };
-use ark_relations::r1cs::ConstraintSynthesizer;
-use std::str::FromStr;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use std::borrow::Borrow;
 
-#[derive(Clone)]
-pub struct AgeCircuit {
+/// `Issuance::generate_constraints`의 결과: credential 번호 비트와 Issuer의 leaf에 등록된 revocation root
+type IssuanceVars = (Vec<Boolean<F>>, Option<FpVar<F>>);
+
+/// credential이 Issuer가 발급한 것임을 보이는 방법
+pub enum Issuance<H: CredentialHasher> {
+    /// Issuer가 publish한 credential 해시 리스트의 Merkle tree에 포함
+    /// credential 번호는 credential tree에서의 leaf 위치
+    Published {
+        credential_root: H::Digest, // public input. Issuer가 publish한 credential 해시들의 Merkle root
+        root_encoding: DigestEncoding, // SHA256 credential root를 public input으로 제공하는 방식
+        membership_path: CredentialPath<H>, // witness. credential 해시에서 root까지의 Merkle path
+    },
+    /// Issuer의 EdDSA 서명. Issuer는 credential마다 publish할 필요 없이 공개 키 하나만 공개
+    /// credential 번호는 서명에 포함된 번호
+    Signed {
+        issuer_public_key: EdwardsAffine, // public input (x, y)
+        signature: CredentialSignature,   // witness
+    },
+    /// Verifier가 신뢰하는 Issuer들(`IssuerSet`) 중 하나의 EdDSA 서명
+    /// credential의 issuer_id, 서명을 검증한 공개 키와 revocation root가 issuer set에 함께 등록되어 있어야 함
+    TrustedIssuers {
+        issuer_set_root: F, // public input. Verifier가 공개한 issuer set의 root
+        revealed_issuer: Option<[u8; 32]>, // public input. Some이면 credential의 issuer_id를 공개
+        issuer_public_key: EdwardsAffine, // witness
+        issuer_path: IssuerPath, // witness. Issuer의 leaf에서 issuer set root까지의 Merkle path
+        signature: CredentialSignature, // witness
+    },
+}
+
+/// circuit의 구조를 결정하는 `Issuance`의 종류. setup에 사용
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IssuanceMode {
+    Published(DigestEncoding),
+    Signed,
+    TrustedIssuers { reveal_issuer: bool },
+}
+
+/// Verifier가 아는 발급 정보. `Issuance`에서 witness를 뺀 public input 부분
+/// Verifier는 이 값으로 `Issuance`와 같은 인코딩(root의 비트 순서 포함)의 public input을 만듦
+pub enum PublicIssuance<H: CredentialHasher> {
+    Published {
+        credential_root: H::Digest,
+        root_encoding: DigestEncoding,
+    },
+    Signed {
+        issuer_public_key: EdwardsAffine,
+    },
+    TrustedIssuers {
+        issuer_set_root: F,
+        revealed_issuer: Option<[u8; 32]>,
+    },
+}
+
+impl<H: CredentialHasher> PublicIssuance<H> {
+    /// circuit의 `generate_constraints`와 동일한 순서의 public input
+    pub fn public_inputs(&self) -> Vec<F> {
+        match self {
+            PublicIssuance::Published {
+                credential_root,
+                root_encoding,
+            } => H::root_to_field_elements(credential_root, *root_encoding),
+            PublicIssuance::Signed { issuer_public_key } => {
+                vec![issuer_public_key.x, issuer_public_key.y]
+            }
+            PublicIssuance::TrustedIssuers {
+                issuer_set_root,
+                revealed_issuer,
+            } => {
+                let mut inputs = vec![*issuer_set_root];
+                if let Some(issuer_id) = revealed_issuer {
+                    inputs.push(issuer_id_field(issuer_id));
+                }
+                inputs
+            }
+        }
+    }
+}
+
+impl<H: CredentialHasher> Clone for Issuance<H> {
+    fn clone(&self) -> Self {
+        match self {
+            Issuance::Published {
+                credential_root,
+                root_encoding,
+                membership_path,
+            } => Issuance::Published {
+                credential_root: credential_root.clone(),
+                root_encoding: *root_encoding,
+                membership_path: membership_path.clone(),
+            },
+            Issuance::Signed {
+                issuer_public_key,
+                signature,
+            } => Issuance::Signed {
+                issuer_public_key: *issuer_public_key,
+                signature: signature.clone(),
+            },
+            Issuance::TrustedIssuers {
+                issuer_set_root,
+                revealed_issuer,
+                issuer_public_key,
+                issuer_path,
+                signature,
+            } => Issuance::TrustedIssuers {
+                issuer_set_root: *issuer_set_root,
+                revealed_issuer: *revealed_issuer,
+                issuer_public_key: *issuer_public_key,
+                issuer_path: issuer_path.clone(),
+                signature: signature.clone(),
+            },
+        }
+    }
+}
+
+impl<H: CredentialHasher> Issuance<H> {
+    pub fn mode(&self) -> IssuanceMode {
+        match self {
+            Issuance::Published { root_encoding, .. } => IssuanceMode::Published(*root_encoding),
+            Issuance::Signed { .. } => IssuanceMode::Signed,
+            Issuance::TrustedIssuers {
+                revealed_issuer, ..
+            } => IssuanceMode::TrustedIssuers {
+                reveal_issuer: revealed_issuer.is_some(),
+            },
+        }
+    }
+
+    // witness를 뺀 public input 부분
+    pub fn public(&self) -> PublicIssuance<H> {
+        match self {
+            Issuance::Published {
+                credential_root,
+                root_encoding,
+                ..
+            } => PublicIssuance::Published {
+                credential_root: credential_root.clone(),
+                root_encoding: *root_encoding,
+            },
+            Issuance::Signed {
+                issuer_public_key, ..
+            } => PublicIssuance::Signed {
+                issuer_public_key: *issuer_public_key,
+            },
+            Issuance::TrustedIssuers {
+                issuer_set_root,
+                revealed_issuer,
+                ..
+            } => PublicIssuance::TrustedIssuers {
+                issuer_set_root: *issuer_set_root,
+                revealed_issuer: *revealed_issuer,
+            },
+        }
+    }
+
+    /// circuit의 `generate_constraints`와 동일한 순서의 public input
+    pub fn public_inputs(&self) -> Vec<F> {
+        self.public().public_inputs()
+    }
+
+    /// public input과 witness를 할당하고 Issuer가 발급한 credential인지 확인
+    /// credential 번호의 Little-endian 비트(`serial_len`개)와,
+    /// `TrustedIssuers`이면 Issuer의 leaf에 등록된 revocation root(`revocation_root`로 할당)를 반환
+    pub fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<F>,
+        credential_var: &CredentialVar,
+        serial_len: usize,
+        revocation_root: F,
+        sections: &mut ConstraintSections,
+    ) -> Result<IssuanceVars, SynthesisError> {
+        match self {
+            Issuance::Published {
+                credential_root,
+                root_encoding,
+                membership_path,
+            } => section!(sections, cs, Section::Membership, {
+                let credential_root_var =
+                    H::root_input_var(cs.clone(), &credential_root, root_encoding)?;
+                let membership_path_var =
+                    CredentialPathVar::<H>::new_witness(cs.clone(), || Ok(membership_path))?;
+
+                // credential의 해시(leaf)에서 시작하여 계산한 root가 public input의 root와 같아야 함
+                // leaf는 Issuer가 해시할 때와 같은 `H::encode_var`로 직렬화
+                let leaf_params_var = H::leaf_params_var(cs.clone())?;
+                let two_to_one_params_var = H::two_to_one_params_var(cs.clone())?;
+                let leaf_var = H::encode_var(credential_var)?;
+
+                let is_valid_credential = membership_path_var.verify_membership(
+                    &leaf_params_var,
+                    &two_to_one_params_var,
+                    &credential_root_var,
+                    leaf_var.borrow(),
+                )?;
+                is_valid_credential.enforce_equal(&Boolean::TRUE)?;
+
+                Ok((membership_path_var.get_leaf_position(), None))
+            }),
+            Issuance::Signed {
+                issuer_public_key,
+                signature,
+            } => section!(sections, cs, Section::Signature, {
+                let issuer_public_key_var =
+                    EdwardsVar::new_input(cs.clone(), || Ok(issuer_public_key))?;
+                let serial_bits = verify_signature_var(
+                    cs.clone(),
+                    &issuer_public_key_var,
+                    signature,
+                    credential_var,
+                    serial_len,
+                )?;
+                Ok((serial_bits, None))
+            }),
+            Issuance::TrustedIssuers {
+                issuer_set_root,
+                revealed_issuer,
+                issuer_public_key,
+                issuer_path,
+                signature,
+            } => {
+                let (issuer_public_key_var, issuer_revocation_root_var) =
+                    section!(sections, cs, Section::Membership, {
+                        let issuer_set_root_var =
+                            FpVar::new_input(cs.clone(), || Ok(issuer_set_root))?;
+                        let issuer_id_var = credential_var.issuer_id_field()?;
+                        if let Some(issuer_id) = revealed_issuer {
+                            let revealed_issuer_id_var =
+                                FpVar::new_input(cs.clone(), || Ok(issuer_id_field(&issuer_id)))?;
+                            revealed_issuer_id_var.enforce_equal(&issuer_id_var)?;
+                        }
+                        let issuer_public_key_var =
+                            EdwardsVar::new_witness(cs.clone(), || Ok(issuer_public_key))?;
+                        let issuer_path_var =
+                            IssuerPathVar::new_witness(cs.clone(), || Ok(issuer_path))?;
+                        let issuer_revocation_root_var =
+                            FpVar::new_witness(cs.clone(), || Ok(revocation_root))?;
+
+                        // credential의 issuer_id, 공개 키와 revocation root의 leaf가 issuer set에 있어야 함
+                        let poseidon_params_var = PoseidonHasher::leaf_params_var(cs.clone())?;
+                        let issuer_leaf_var = [
+                            issuer_id_var,
+                            issuer_public_key_var.x.clone(),
+                            issuer_public_key_var.y.clone(),
+                            issuer_revocation_root_var.clone(),
+                        ];
+                        let is_trusted_issuer = issuer_path_var.verify_membership(
+                            &poseidon_params_var,
+                            &poseidon_params_var,
+                            &issuer_set_root_var,
+                            &issuer_leaf_var[..],
+                        )?;
+                        is_trusted_issuer.enforce_equal(&Boolean::TRUE)?;
+                        Ok::<_, SynthesisError>((issuer_public_key_var, issuer_revocation_root_var))
+                    })?;
+
+                let serial_bits = section!(sections, cs, Section::Signature, {
+                    verify_signature_var(
+                        cs.clone(),
+                        &issuer_public_key_var,
+                        signature,
+                        credential_var,
+                        serial_len,
+                    )
+                })?;
+                Ok((serial_bits, Some(issuer_revocation_root_var)))
+            }
+        }
+    }
+}
+
+// Issuer 공개 키로 credential과 번호에 대한 서명 검증. 번호의 Little-endian 비트(`serial_len`개)를 반환
+fn verify_signature_var(
+    cs: ConstraintSystemRef<F>,
+    issuer_public_key_var: &EdwardsVar,
+    signature: CredentialSignature,
+    credential_var: &CredentialVar,
+    serial_len: usize,
+) -> Result<Vec<Boolean<F>>, SynthesisError> {
+    let signature_var = CredentialSignatureVar::new_witness(cs.clone(), || Ok(signature))?;
+    let is_valid_signature = signature_var.verify(cs, issuer_public_key_var, credential_var)?;
+    is_valid_signature.enforce_equal(&Boolean::TRUE)?;
+
+    // 번호는 revocation tree의 leaf 개수보다 작아야 함
+    for bit in &signature_var.serial[serial_len..] {
+        bit.enforce_equal(&Boolean::FALSE)?;
+    }
+    Ok(signature_var.serial[..serial_len].to_vec())
+}
+
+pub struct AgeCircuit<H: CredentialHasher> {
     // public input
-    pub dob_cutoff_year: String,
-    pub hashed_credentials: [Sha256Digest; MAX_CREDENTIALS],
+    pub today: Date,            // 나이를 계산하는 기준 날짜 (Verifier가 제시)
+    pub predicate: Predicate,   // credential 속성에 대한 조건식. 비교하는 값들이 public input
+    pub disclosure: Disclosure, // Holder가 공개하는 credential 속성. 공개한 값들이 public input
+    pub issuance: Issuance<H>, // Issuer가 발급한 credential임을 보이는 방법. H는 `Published`의 해시 방식
+    pub revocation_root: F,    // Issuer가 publish한 폐기 credential 번호들의 revocation tree root
+    pub scope: F, // 증명을 받는 Verifier의 scope. nullifier는 circuit이 Holder 비밀 키로 계산하여 공개
+    pub challenge: F, // Verifier가 이번 증명 요청에 발급한 challenge. circuit이 credential 해시와 함께 해시하여 공개
 
     // witness
+    pub holder_secret_key: F, // credential에 들어 있는 Holder 공개 키의 비밀 키
     pub credential: Credential,
+    pub non_revocation_path: RevocationPath, // credential 번호의 leaf에서 revocation root까지의 Merkle path
+
+    // circuit 구조
+    pub encoding: CredentialEncoding, // credential을 circuit에 할당하는 방식
+}
+
+impl<H: CredentialHasher> Clone for AgeCircuit<H> {
+    fn clone(&self) -> Self {
+        AgeCircuit {
+            today: self.today,
+            predicate: self.predicate.clone(),
+            disclosure: self.disclosure.clone(),
+            issuance: self.issuance.clone(),
+            revocation_root: self.revocation_root,
+            scope: self.scope,
+            challenge: self.challenge,
+            holder_secret_key: self.holder_secret_key,
+            credential: self.credential.clone(),
+            non_revocation_path: self.non_revocation_path.clone(),
+            encoding: self.encoding,
+        }
+    }
+}
+
+impl<H: CredentialHasher> AgeCircuit<H> {
+    // 증명과 함께 Verifier에게 제출하는 nullifier. circuit이 public input으로 공개하는 값과 같음
+    pub fn nullifier(&self) -> F {
+        nullifier::nullifier(self.holder_secret_key, self.scope)
+    }
+
+    // 증명과 함께 제출하는 challenge binding. circuit이 public input으로 공개하는 값과 같음
+    pub fn challenge_binding(&self) -> F {
+        challenge::challenge_binding(self.holder_secret_key, &self.credential, self.challenge)
+    }
+
+    /// Holder: `generate_constraints`와 같은 순서의 모든 public input
+    /// 기준 날짜, 조건식의 값, 공개한 속성 값, `Issuance`의 값, revocation root, scope, challenge, challenge binding, nullifier
+    pub fn public_inputs(&self) -> Result<Vec<F>, Error> {
+        let policy = Policy {
+            today: self.today,
+            schema: self.credential.schema.clone(),
+            predicate: self.predicate.clone(),
+            disclosure: self.disclosure.clone(),
+            challenge: self.challenge,
+        };
+        let submitted = Submitted {
+            disclosed: self.disclosure.reveal(&self.credential)?,
+            challenge_binding: self.challenge_binding(),
+            nullifier: self.nullifier(),
+        };
+        policy.public_inputs(
+            &self.issuance.public(),
+            self.revocation_root,
+            self.scope,
+            &submitted,
+        )
+    }
 }
 
-impl ConstraintSynthesizer<F> for AgeCircuit {
+impl<H: CredentialHasher> ConstraintSynthesizer<F> for AgeCircuit<H> {
     fn generate_constraints(
         self,
         cs: ark_relations::r1cs::ConstraintSystemRef<F>,
     ) -> ark_relations::r1cs::Result<()> {
+        self.generate_sectioned_constraints(cs, &mut ConstraintSections::new())
+    }
+}
+
+impl<H: CredentialHasher> SectionedCircuit for AgeCircuit<H> {
+    fn generate_sectioned_constraints(
+        self,
+        cs: ConstraintSystemRef<F>,
+        sections: &mut ConstraintSections,
+    ) -> Result<(), SynthesisError> {
         // -------------------- public input 할당 --------------------
         // circuit 안에서 사용하는 값은 변수명에 _var 접미사를 붙임
-        let dob_year_var = FpVar::new_input(cs.clone(), || {
-            Ok(F::from_str(&self.dob_cutoff_year).unwrap())
-        })?;
-
-        let credential_hash_var_1 =
-            DigestVar::<F>::new_input(cs.clone(), || Ok(self.hashed_credentials[0].clone()))?;
+        let nullifier = self.nullifier();
+        let challenge_binding = self.challenge_binding();
+        let today_var = FpVar::new_input(cs.clone(), || Ok(self.today.to_field()))?;
 
-        let credential_hash_var_2 =
-            DigestVar::<F>::new_input(cs.clone(), || Ok(self.hashed_credentials[1].clone()))?;
+        // 1, 2, 6, 7, 8. credential에 대한 constraint (조건식, 공개 값, 발급 확인, revocation root의 public input도 할당)
+        let claim = CredentialClaim {
+            predicate: self.predicate,
+            disclosure: self.disclosure,
+            issuance: self.issuance,
+            revocation_root: self.revocation_root,
+            credential: self.credential,
+            non_revocation_path: self.non_revocation_path,
+            encoding: self.encoding,
+        };
+        let credential_var = claim.generate_constraints(cs.clone(), &today_var, sections)?;
 
-        let credential_hash_var_3 =
-            DigestVar::<F>::new_input(cs.clone(), || Ok(self.hashed_credentials[2].clone()))?;
+        let scope_var = FpVar::new_input(cs.clone(), || Ok(self.scope))?;
+        let challenge_var = FpVar::new_input(cs.clone(), || Ok(self.challenge))?;
+        let challenge_binding_var = FpVar::new_input(cs.clone(), || Ok(challenge_binding))?;
+        let nullifier_var = FpVar::new_input(cs.clone(), || Ok(nullifier))?;
 
         // -------------------- witness 할당 --------------------
+        let holder_secret_key_var = FpVar::new_witness(cs.clone(), || Ok(self.holder_secret_key))?;
 
-        let holder_dob_year_var = FpVar::new_witness(cs.clone(), || {
-            Ok(F::from_str(&self.credential.holder_dob_year).unwrap())
+        // -------------------- constraints --------------------
+        // 3. credential을 발급받은 Holder인지 확인
+        // 비밀 키로 계산한 공개 키가 credential의 Holder 공개 키와 같아야 함
+        section!(sections, cs, Section::HolderKey, {
+            let holder_public_key_var = public_key_var(cs.clone(), &holder_secret_key_var)?;
+            holder_public_key_var.enforce_equal(&credential_var.holder_public_key)
         })?;
 
-        // -------------------- constraints --------------------
-        // hash 계산
-        let mut sha256_var = Sha256Gadget::default();
-
-        sha256_var
-            .update(&to_byte_vars(cs.clone(), &self.credential.issuer_id))
-            .unwrap();
-        sha256_var
-            .update(&to_byte_vars(
+        // 4. nullifier가 Holder 비밀 키와 scope로 계산한 값인지 확인
+        // 같은 Holder가 같은 Verifier에게 다시 증명하면 같은 nullifier가 공개됨
+        section!(sections, cs, Section::Nullifier, {
+            let expected_nullifier_var =
+                nullifier::nullifier_var(cs.clone(), &holder_secret_key_var, &scope_var)?;
+            nullifier_var.enforce_equal(&expected_nullifier_var)
+        })?;
+
+        // 5. challenge binding이 Holder 비밀 키, credential 해시와 challenge로 계산한 값인지 확인
+        // challenge가 다른 증명 요청에는 증명을 재사용할 수 없음
+        section!(sections, cs, Section::ChallengeBinding, {
+            let expected_challenge_binding_var = challenge::challenge_binding_var(
                 cs.clone(),
-                &string_to_bytes(&self.credential.holder_name),
-            ))
-            .unwrap();
-        sha256_var
-            .update(&holder_dob_year_var.to_bytes().unwrap())
-            .unwrap();
-        sha256_var
-            .update(&to_byte_vars(
+                &holder_secret_key_var,
+                &credential_var,
+                &challenge_var,
+            )?;
+            challenge_binding_var.enforce_equal(&expected_challenge_binding_var)
+        })
+    }
+}
+
+/// 증명에 사용하는 credential 하나와 그 credential에 대해 증명하는 내용
+/// `AgeCircuit`과 `MultiCredentialCircuit`이 credential마다 같은 constraint를 사용
+pub struct CredentialClaim<H: CredentialHasher> {
+    // public input
+    pub predicate: Predicate, // credential 속성에 대한 조건식. 비교하는 값들이 public input
+    pub disclosure: Disclosure, // 공개하는 credential 속성. 공개한 값들이 public input
+    pub issuance: Issuance<H>, // Issuer가 발급한 credential임을 보이는 방법
+    pub revocation_root: F,   // Issuer가 publish한 revocation tree root
+
+    // witness
+    pub credential: Credential,
+    pub non_revocation_path: RevocationPath,
+
+    // circuit 구조
+    pub encoding: CredentialEncoding, // credential을 circuit에 할당하는 방식
+}
+
+impl<H: CredentialHasher> Clone for CredentialClaim<H> {
+    fn clone(&self) -> Self {
+        CredentialClaim {
+            predicate: self.predicate.clone(),
+            disclosure: self.disclosure.clone(),
+            issuance: self.issuance.clone(),
+            revocation_root: self.revocation_root,
+            credential: self.credential.clone(),
+            non_revocation_path: self.non_revocation_path.clone(),
+            encoding: self.encoding,
+        }
+    }
+}
+
+impl<H: CredentialHasher> CredentialClaim<H> {
+    /// credential을 witness로 할당하고 발급, 폐기, 유효 기간, 조건식, 공개 값을 확인
+    /// public input은 조건식의 값, 공개한 속성 값, `Issuance`의 값, revocation root 순서로 할당
+    /// Holder 공개 키는 확인하지 않으므로 호출하는 circuit이 Holder 비밀 키로 확인해야 함
+    pub fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<F>,
+        today_var: &FpVar<F>,
+        sections: &mut ConstraintSections,
+    ) -> Result<CredentialVar, SynthesisError> {
+        // 조건식의 값은 조건식 constraint를 만들면서 할당하므로 credential을 먼저 witness로 할당
+        let credential_var = section!(sections, cs, Section::Credential, {
+            CredentialVar::new_encoded(
                 cs.clone(),
-                &string_to_bytes(&self.credential.randomness),
-            ))
-            .unwrap();
+                self.credential,
+                AllocationMode::Witness,
+                self.encoding,
+            )
+        })?;
+        let predicate_var = section!(sections, cs, Section::Predicate, {
+            self.predicate
+                .generate_constraints(cs.clone(), &credential_var, today_var)
+        })?;
+        // 8. 공개한 속성 값이 credential의 값과 같은지 확인 (공개한 값은 조건식의 값 다음 public input)
+        section!(sections, cs, Section::Disclosure, {
+            self.disclosure
+                .generate_constraints(cs.clone(), &credential_var)
+        })?;
 
-        let sha256_var = sha256_var.finalize().unwrap();
+        // credential 번호는 revocation tree의 leaf 위치 (tree 높이 - 1 비트)
+        // 1. Issuer가 발급한 credential이 맞는지 확인 (public input과 witness도 할당)
+        let serial_len = self.non_revocation_path.auth_path.len() + 1;
+        let (serial_bits, issuer_revocation_root_var) = self.issuance.generate_constraints(
+            cs.clone(),
+            &credential_var,
+            serial_len,
+            self.revocation_root,
+            sections,
+        )?;
 
-        // 1. Issuer가 발급한 credential이 맞는지 확인
-        let is_eq1 = sha256_var.is_eq(&credential_hash_var_1)?;
-        let is_eq2 = sha256_var.is_eq(&credential_hash_var_2)?;
-        let is_eq3 = sha256_var.is_eq(&credential_hash_var_3)?;
+        // 2. credential이 폐기되지 않았는지 확인
+        // revocation tree에서 credential 번호의 leaf가 `not_revoked_leaf()`여야 함
+        // 다른 번호의 path를 쓸 수 없도록 leaf 위치를 1.에서 확인한 번호로 정함
+        // issuer set으로 발급을 확인했으면 revocation root는 credential을 발급한 Issuer의 root여야 함
+        section!(sections, cs, Section::Revocation, {
+            let revocation_root_var = FpVar::new_input(cs.clone(), || Ok(self.revocation_root))?;
+            if let Some(issuer_revocation_root_var) = issuer_revocation_root_var {
+                revocation_root_var.enforce_equal(&issuer_revocation_root_var)?;
+            }
+            let mut non_revocation_path_var =
+                RevocationPathVar::new_witness(cs.clone(), || Ok(self.non_revocation_path))?;
+            non_revocation_path_var.set_leaf_position(serial_bits);
+            let poseidon_params_var = PoseidonHasher::leaf_params_var(cs.clone())?;
+            let not_revoked_leaf_var = not_revoked_leaf().map(FpVar::Constant);
 
-        let is_valid_credential = Boolean::kary_or(&[is_eq1, is_eq2, is_eq3]).unwrap();
-        is_valid_credential.enforce_equal(&Boolean::TRUE)?;
+            let is_not_revoked = non_revocation_path_var.verify_membership(
+                &poseidon_params_var,
+                &poseidon_params_var,
+                &revocation_root_var,
+                &not_revoked_leaf_var[..],
+            )?;
+            is_not_revoked.enforce_equal(&Boolean::TRUE)
+        })?;
+
+        // 6. 기준 날짜가 credential의 유효 기간(발급일 ~ 만료일) 안인지 확인
+        section!(sections, cs, Section::Validity, {
+            credential_var.enforce_valid_on(today_var)
+        })?;
 
-        // 2. 성인 여부 확인
-        holder_dob_year_var.enforce_cmp(&dob_year_var, std::cmp::Ordering::Less, true)?;
+        // 7. credential 속성이 조건식을 만족하는지 확인 (예: 만 19세 이상)
+        section!(sections, cs, Section::Predicate, {
+            predicate_var.enforce_equal(&Boolean::TRUE)
+        })?;
 
-        Ok(())
+        Ok(credential_var)
     }
 }
@@ -1,37 +1,365 @@
-use ark_crypto_primitives::crh::sha256::{Sha256, digest::Digest};
+use std::borrow::Borrow;
 
-use crate::{Sha256Digest, utils::utils::string_to_bytes};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_r1cs_std::{
+    R1CSVar, ToBitsGadget, ToBytesGadget,
+    alloc::{AllocVar, AllocationMode},
+    eq::EqGadget,
+    fields::fp::FpVar,
+    prelude::Boolean,
+    uint8::UInt8,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use serde::{Deserialize, Deserializer, Serialize, de};
 
-#[derive(Clone)]
+use crate::{
+    F,
+    data_structures::{
+        date::{Date, Validity},
+        issuer_set::issuer_id_field,
+        schema::{AttributeType, Schema, SchemaError, Value},
+    },
+    utils::{
+        serialization::canonical,
+        utils::{MAX_STRING_BYTES, field_to_bytes},
+    },
+};
+
+// 유효 기간 비교에서 두 날짜(YYYYMMDD)의 차이를 나타내는 비트 수
+const DATE_DIFF_BITS: usize = 32;
+// `Integer` 속성의 비트 수 (u64)
+const INTEGER_BITS: usize = 64;
+
+/// Issuer가 schema에 따라 발급한 credential
+#[derive(Clone, Debug, Serialize)]
 pub struct Credential {
     pub issuer_id: [u8; 32],
-    pub holder_name: String,
-    pub holder_dob_year: String,
-    pub randomness: String,
+    #[serde(with = "canonical")]
+    pub holder_public_key: F, // credential을 발급받은 Holder의 공개 키
+    pub validity: Validity, // 발급일과 만료일. 해시에 포함되어 Holder가 바꿀 수 없음
+    pub schema: Schema,
+    attributes: Vec<Value>, // schema에 선언한 순서
+    #[serde(with = "canonical")]
+    pub randomness: F, // credential 해시로 속성을 알아낼 수 없게 하는 비밀 값
+}
+
+// 파일 등에서 읽을 때도 속성 값이 schema에 맞는지 확인
+impl<'de> Deserialize<'de> for Credential {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            issuer_id: [u8; 32],
+            #[serde(with = "canonical")]
+            holder_public_key: F,
+            validity: Validity,
+            schema: Schema,
+            attributes: Vec<Value>,
+            #[serde(with = "canonical")]
+            randomness: F,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        fields
+            .schema
+            .check_values(&fields.attributes)
+            .map_err(de::Error::custom)?;
+        Ok(Credential {
+            issuer_id: fields.issuer_id,
+            holder_public_key: fields.holder_public_key,
+            validity: fields.validity,
+            schema: fields.schema,
+            attributes: fields.attributes,
+            randomness: fields.randomness,
+        })
+    }
 }
 
 impl Credential {
+    // 모든 속성의 값이 schema에 맞아야 발급 가능
     pub fn new(
         issuer_id: [u8; 32],
-        holder_name: String,
-        holder_dob_year: String,
-        randomness: String,
-    ) -> Self {
+        holder_public_key: F,
+        validity: Validity,
+        schema: &Schema,
+        attributes: Vec<(&str, Value)>,
+        randomness: F,
+    ) -> Result<Self, SchemaError> {
+        Ok(Credential {
+            issuer_id,
+            holder_public_key,
+            validity,
+            schema: schema.clone(),
+            attributes: schema.order_values(attributes)?,
+            randomness,
+        })
+    }
+
+    // setup에서 circuit의 구조를 정하는 데만 사용하는 credential
+    pub fn mock(schema: &Schema) -> Self {
         Credential {
+            issuer_id: [0u8; 32],
+            holder_public_key: F::zero(),
+            validity: Validity::new(Date::UNIX_EPOCH, Date::UNIX_EPOCH)
+                .expect("same issuance and expiry date"),
+            schema: schema.clone(),
+            attributes: schema.default_values(),
+            randomness: F::zero(),
+        }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&Value> {
+        self.schema.index_of(name).map(|i| &self.attributes[i])
+    }
+
+    // circuit에 할당되는 속성 값
+    pub fn attribute_field(&self, name: &str) -> Option<F> {
+        self.schema
+            .index_of(name)
+            .map(|i| self.attribute_fields()[i])
+    }
+
+    // 모든 속성을 schema 순서대로 field 원소로 변환
+    fn attribute_fields(&self) -> Vec<F> {
+        self.schema
+            .attributes()
+            .iter()
+            .zip(&self.attributes)
+            .map(|((_, attribute_type), value)| {
+                attribute_type
+                    .encode(value)
+                    .expect("validated against the schema on creation")
+            })
+            .collect()
+    }
+
+    // Credential을 바이트로 직렬화. SHA256, Pedersen credential tree의 leaf로 사용
+    // issuer_id, schema id, holder 공개 키, 발급일, 만료일(YYYYMMDD), schema 순서의 속성들(field 원소), randomness를 32바이트씩 이어붙임
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.issuer_id.to_vec();
+        bytes.extend(field_to_bytes(self.schema.id()));
+        bytes.extend(field_to_bytes(self.holder_public_key));
+        bytes.extend(field_to_bytes(self.validity.issued_at().to_field()));
+        bytes.extend(field_to_bytes(self.validity.expires_at().to_field()));
+        for field in self.attribute_fields() {
+            bytes.extend(field_to_bytes(field));
+        }
+        bytes.extend(field_to_bytes(self.randomness));
+        bytes
+    }
+
+    // Credential을 field 원소로 직렬화. Poseidon credential tree의 leaf로 사용
+    pub fn to_field_elements(&self) -> Vec<F> {
+        self.to_bytes()
+            .chunks(32)
+            .map(F::from_le_bytes_mod_order)
+            .collect()
+    }
+}
+
+/// circuit에 credential을 할당하는 방식. circuit의 구조가 달라지므로 setup에도 같은 방식을 사용
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CredentialEncoding {
+    /// issuer_id와 randomness를 바이트로 할당. 바이트 값은 credential 해시로만 확인
+    #[default]
+    Bytes,
+    /// 모든 값을 field 원소로 할당하고 circuit 안에서 canonical 비트(modulus보다 작은 값)로 분해
+    /// issuer_id와 `String` 속성은 `string_to_bytes`의 인코딩, `Integer`는 64비트, `Enum`은 선언한 값의 index인지 확인
+    /// native 직렬화와 다른 바이트로 같은 해시를 만들 수 없음
+    Canonical,
+}
+
+/// circuit 안에서 사용하는 `Credential`
+/// holder 공개 키, 발급일, 만료일과 속성은 schema에 따라 하나씩 field 원소로, issuer_id와 randomness는 바이트로 할당
+/// (`CredentialEncoding::Canonical`이면 issuer_id와 randomness도 field 원소로 할당한 뒤 바이트로 분해)
+/// schema id는 상수. setup에서 Verifier가 정한 schema의 id가 키에 들어가므로 다른 schema의 credential로는 검증되는 증명을 만들 수 없음
+#[derive(Clone)]
+pub struct CredentialVar {
+    pub issuer_id: Vec<UInt8<F>>,
+    pub schema_id: FpVar<F>,
+    pub holder_public_key: FpVar<F>,
+    pub issued_at: FpVar<F>,
+    pub expires_at: FpVar<F>,
+    pub schema: Schema,
+    pub attributes: Vec<FpVar<F>>,
+    pub randomness: Vec<UInt8<F>>,
+}
+
+impl AllocVar<Credential, F> for CredentialVar {
+    // 할당할 속성의 개수와 순서는 credential의 schema로 결정하므로 값이 없으면 할당할 수 없음
+    fn new_variable<T: Borrow<Credential>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let credential = f()?.borrow().clone();
+        CredentialVar::new_encoded(cs, credential, mode, CredentialEncoding::Bytes)
+    }
+}
+
+impl CredentialVar {
+    /// `encoding` 방식으로 credential 할당
+    pub fn new_encoded(
+        cs: impl Into<Namespace<F>>,
+        credential: Credential,
+        mode: AllocationMode,
+        encoding: CredentialEncoding,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let (issuer_id, randomness) = match encoding {
+            CredentialEncoding::Bytes => (
+                Vec::new_variable(cs.clone(), || Ok(credential.issuer_id), mode)?,
+                Vec::new_variable(
+                    cs.clone(),
+                    || Ok(field_to_bytes(credential.randomness)),
+                    mode,
+                )?,
+            ),
+            CredentialEncoding::Canonical => {
+                let issuer_id_var = FpVar::new_variable(
+                    cs.clone(),
+                    || Ok(issuer_id_field(&credential.issuer_id)),
+                    mode,
+                )?;
+                let issuer_id = issuer_id_var.to_bytes()?;
+                enforce_string_encoding(&issuer_id)?;
+                let randomness_var =
+                    FpVar::new_variable(cs.clone(), || Ok(credential.randomness), mode)?;
+                (issuer_id, randomness_var.to_bytes()?)
+            }
+        };
+
+        let attributes: Vec<FpVar<F>> = credential
+            .attribute_fields()
+            .into_iter()
+            .map(|field| FpVar::new_variable(cs.clone(), || Ok(field), mode))
+            .collect::<Result<_, _>>()?;
+        if encoding == CredentialEncoding::Canonical {
+            for ((_, attribute_type), attribute) in
+                credential.schema.attributes().iter().zip(&attributes)
+            {
+                enforce_attribute_encoding(attribute_type, attribute)?;
+            }
+        }
+
+        Ok(CredentialVar {
             issuer_id,
-            holder_name,
-            holder_dob_year,
+            schema_id: FpVar::Constant(credential.schema.id()),
+            holder_public_key: FpVar::new_variable(
+                cs.clone(),
+                || Ok(credential.holder_public_key),
+                mode,
+            )?,
+            issued_at: FpVar::new_variable(
+                cs.clone(),
+                || Ok(credential.validity.issued_at().to_field()),
+                mode,
+            )?,
+            expires_at: FpVar::new_variable(
+                cs.clone(),
+                || Ok(credential.validity.expires_at().to_field()),
+                mode,
+            )?,
+            attributes,
             randomness,
+            schema: credential.schema,
+        })
+    }
+}
+
+// 날짜(YYYYMMDD) `a <= b`. 차이 `b - a`가 32비트로 표현되는지 확인
+// (`is_cmp`는 두 값을 모두 field 크기만큼 비트 분해하므로 constraint가 훨씬 많음)
+fn enforce_date_le(a: &FpVar<F>, b: &FpVar<F>) -> Result<(), SynthesisError> {
+    enforce_bit_length(&(b - a), DATE_DIFF_BITS)
+}
+
+// `value`가 `num_bits`비트로 표현되는지 확인
+fn enforce_bit_length(value: &FpVar<F>, num_bits: usize) -> Result<(), SynthesisError> {
+    // setup에서는 값이 없으므로 비트마다 할당 (값은 할당할 때만 계산)
+    let bits = (0..num_bits)
+        .map(|i| Boolean::new_witness(value.cs(), || Ok(value.value()?.into_bigint().get_bit(i))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(value)
+}
+
+// 32바이트가 `string_to_bytes`의 인코딩인지 확인
+// 마지막 바이트는 길이(MAX_STRING_BYTES 이하)이고 길이 이후의 바이트는 0
+fn enforce_string_encoding(bytes: &[UInt8<F>]) -> Result<(), SynthesisError> {
+    let length_bits = bytes[MAX_STRING_BYTES].to_bits_le()?;
+    // MAX_STRING_BYTES(31)는 5비트
+    for bit in &length_bits[5..] {
+        bit.enforce_equal(&Boolean::FALSE)?;
+    }
+    let length_var = Boolean::le_bits_to_fp_var(&length_bits)?;
+
+    let mut is_padding = Boolean::FALSE;
+    for (i, byte) in bytes[..MAX_STRING_BYTES].iter().enumerate() {
+        is_padding = is_padding.or(&length_var.is_eq(&FpVar::Constant(F::from(i as u64)))?)?;
+        byte.conditional_enforce_equal(&UInt8::constant(0), &is_padding)?;
+    }
+    Ok(())
+}
+
+// 속성 값이 `AttributeType::encode`로 만들 수 있는 값인지 확인
+// `Date`는 유효 기간 / 조건식에서 YYYYMMDD 값으로만 비교하므로 확인하지 않음
+fn enforce_attribute_encoding(
+    attribute_type: &AttributeType,
+    attribute: &FpVar<F>,
+) -> Result<(), SynthesisError> {
+    match attribute_type {
+        AttributeType::Integer => enforce_bit_length(attribute, INTEGER_BITS),
+        AttributeType::Date => Ok(()),
+        AttributeType::String => enforce_string_encoding(&attribute.to_bytes()?),
+        // (값 - 0)(값 - 1)...(값 - (n - 1)) == 0
+        AttributeType::Enum(variants) => (0..variants.len())
+            .map(|i| attribute - F::from(i as u64))
+            .reduce(|product, factor| product * factor)
+            .expect("schema enums have at least one variant")
+            .enforce_equal(&FpVar::Constant(F::zero())),
+    }
+}
+
+impl CredentialVar {
+    pub fn attribute(&self, name: &str) -> Option<&FpVar<F>> {
+        self.schema.index_of(name).map(|i| &self.attributes[i])
+    }
+
+    // `today`가 유효 기간 안이어야 함. `Validity::contains`와 같음
+    pub fn enforce_valid_on(&self, today: &FpVar<F>) -> Result<(), SynthesisError> {
+        enforce_date_le(&self.issued_at, today)?;
+        enforce_date_le(today, &self.expires_at)
+    }
+
+    // issuer_id를 field 원소로 변환. `issuer_set::issuer_id_field`와 같은 값
+    pub fn issuer_id_field(&self) -> Result<FpVar<F>, SynthesisError> {
+        Boolean::le_bits_to_fp_var(&self.issuer_id.to_bits_le()?)
+    }
+
+    // Credential::to_bytes와 동일한 순서로 직렬화
+    pub fn to_bytes(&self) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let mut bytes = self.issuer_id.clone();
+        bytes.extend(self.schema_id.to_bytes()?);
+        bytes.extend(self.holder_public_key.to_bytes()?);
+        bytes.extend(self.issued_at.to_bytes()?);
+        bytes.extend(self.expires_at.to_bytes()?);
+        for attribute in &self.attributes {
+            bytes.extend(attribute.to_bytes()?);
         }
+        bytes.extend(self.randomness.iter().cloned());
+        Ok(bytes)
     }
 
-    // Credential의 SHA256 해시 계산
-    pub fn to_sha256(&self) -> Sha256Digest {
-        let mut hasher = Sha256::new();
-        hasher.update(&self.issuer_id);
-        hasher.update(&string_to_bytes(&self.holder_name));
-        hasher.update(&string_to_bytes(&self.holder_dob_year));
-        hasher.update(&string_to_bytes(&self.randomness));
-        hasher.finalize().to_vec()
+    // Credential::to_field_elements와 동일한 순서로 직렬화
+    pub fn to_field_elements(&self) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let mut fields = vec![
+            self.issuer_id_field()?,
+            self.schema_id.clone(),
+            self.holder_public_key.clone(),
+            self.issued_at.clone(),
+            self.expires_at.clone(),
+        ];
+        fields.extend(self.attributes.iter().cloned());
+        fields.push(Boolean::le_bits_to_fp_var(&self.randomness.to_bits_le()?)?);
+        Ok(fields)
     }
 }
@@ -0,0 +1,194 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::F;
+
+/// 날짜를 하나의 field 원소 YYYYMMDD로 묶을 때 연도에 곱하는 값
+/// 월(1~12)과 일(1~31)이 유효하면 묶은 값의 대소 관계가 날짜의 선후 관계와 같음
+pub const DATE_YEAR_SCALE: u64 = 10_000;
+const DATE_MONTH_SCALE: u64 = 100;
+
+/// 그레고리력 날짜. `new` / `from_str`은 존재하는 날짜만 허용
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    year: u32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// 1970-01-01
+    pub const UNIX_EPOCH: Date = Date {
+        year: 1970,
+        month: 1,
+        day: 1,
+    };
+
+    pub fn new(year: u32, month: u32, day: u32) -> Result<Self, DateError> {
+        if !(1..=12).contains(&month) {
+            return Err(DateError::InvalidMonth);
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return Err(DateError::InvalidDay);
+        }
+        Ok(Date { year, month, day })
+    }
+
+    // 시스템 시계 기준 오늘 날짜 (UTC)
+    pub fn today() -> Self {
+        let days = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system clock is before 1970")
+            .as_secs()
+            / 86_400;
+        Self::from_days_since_epoch(days)
+    }
+
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    // YYYYMMDD 형태의 정수
+    pub fn to_u64(&self) -> u64 {
+        self.year as u64 * DATE_YEAR_SCALE + self.month as u64 * DATE_MONTH_SCALE + self.day as u64
+    }
+
+    // YYYYMMDD 형태의 field 원소. circuit 안에서 날짜 비교에 사용
+    pub fn to_field(&self) -> F {
+        F::from(self.to_u64())
+    }
+
+    // `today` 기준 만 나이. 생일 당일에 한 살 증가 (2월 29일생은 평년에는 3월 1일에 증가)
+    pub fn age_on(&self, today: Date) -> u32 {
+        if today < *self {
+            return 0;
+        }
+        let had_birthday = (today.month, today.day) >= (self.month, self.day);
+        today.year - self.year - if had_birthday { 0 } else { 1 }
+    }
+
+    // 1970-01-01부터 지난 일 수를 날짜로 변환 (Howard Hinnant의 civil_from_days)
+    fn from_days_since_epoch(days: u64) -> Self {
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let doe = z % 146_097;
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date {
+            year: year as u32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+}
+
+fn is_leap_year(year: u32) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// "YYYY-MM-DD" 형식
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<u32> = s
+            .split('-')
+            .map(|part| part.parse().map_err(|_| DateError::InvalidFormat))
+            .collect::<Result<_, _>>()?;
+        match parts[..] {
+            [year, month, day] => Date::new(year, month, day),
+            _ => Err(DateError::InvalidFormat),
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// 저장할 때도 "YYYY-MM-DD" 형식. 읽을 때 존재하는 날짜인지 확인
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// credential의 유효 기간. 발급일과 만료일 모두 포함
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validity {
+    issued_at: Date,
+    expires_at: Date,
+}
+
+impl Validity {
+    pub fn new(issued_at: Date, expires_at: Date) -> Result<Self, DateError> {
+        if expires_at < issued_at {
+            return Err(DateError::ExpiresBeforeIssued);
+        }
+        Ok(Validity {
+            issued_at,
+            expires_at,
+        })
+    }
+
+    pub fn issued_at(&self) -> Date {
+        self.issued_at
+    }
+
+    pub fn expires_at(&self) -> Date {
+        self.expires_at
+    }
+
+    // `today`가 유효 기간 안인지. circuit과 같은 방식으로 YYYYMMDD를 비교
+    pub fn contains(&self, today: Date) -> bool {
+        self.issued_at <= today && today <= self.expires_at
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DateError {
+    #[error("date is not in YYYY-MM-DD format")]
+    InvalidFormat,
+    #[error("month is not in 1..=12")]
+    InvalidMonth,
+    #[error("day does not exist in the month")]
+    InvalidDay,
+    #[error("expiry date is before the issuance date")]
+    ExpiresBeforeIssued,
+}
@@ -0,0 +1,165 @@
+use std::{fmt, ops::Range};
+
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError,
+};
+
+use crate::F;
+
+/// circuit의 구간. constraint는 구간마다 `ns!` namespace 안에서 만들고 `ConstraintSections`에 기록
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    Credential,       // credential 할당 (Canonical 인코딩 확인 포함)
+    Predicate,        // 조건식 (예: 나이 확인)
+    Disclosure,       // 공개한 속성 값
+    Membership,       // credential tree 또는 issuer set의 Merkle path
+    Signature,        // Issuer 서명
+    Revocation,       // revocation tree의 non-revocation path
+    Validity,         // 유효 기간
+    HolderKey,        // Holder 비밀 키와 credential의 공개 키
+    Nullifier,        // nullifier
+    ChallengeBinding, // challenge binding
+    Claim(usize),     // `MultiCredentialCircuit`의 credential (0부터)
+}
+
+impl Section {
+    // `ns!`의 namespace 이름. 컴파일 시간에 정해져야 하므로 `Claim`은 index 없이 "claim"
+    pub const fn name(self) -> &'static str {
+        match self {
+            Section::Credential => "credential",
+            Section::Predicate => "predicate check",
+            Section::Disclosure => "disclosure check",
+            Section::Membership => "membership check",
+            Section::Signature => "signature check",
+            Section::Revocation => "revocation check",
+            Section::Validity => "validity check",
+            Section::HolderKey => "holder key check",
+            Section::Nullifier => "nullifier check",
+            Section::ChallengeBinding => "challenge binding check",
+            Section::Claim(_) => "claim",
+        }
+    }
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Section::Claim(index) => write!(f, "claim {index}"),
+            section => f.write_str(section.name()),
+        }
+    }
+}
+
+/// `ns!` namespace를 열고 구간의 constraint를 만든 뒤 `ConstraintSections`에 constraint index 범위를 기록
+/// `$section`은 `Section::Claim`이 아닌 상수여야 함 (namespace 이름은 컴파일 시간에 정해짐)
+/// `$body`는 `Result<_, SynthesisError>`. 안에서 `?`로 끝나도 구간을 닫은 뒤 오류를 반환
+macro_rules! section {
+    ($sections:expr, $cs:expr, $section:expr, $body:expr) => {{
+        let _namespace = ark_relations::ns!($cs, $section.name());
+        $sections.enter(&$cs, $section);
+        #[allow(clippy::redundant_closure_call)]
+        let result = (|| -> Result<_, ark_relations::r1cs::SynthesisError> { $body })();
+        $sections.exit(&$cs);
+        result
+    }};
+}
+pub(crate) use section;
+
+/// circuit을 만들면서 기록한 구간별 constraint index 범위
+#[derive(Clone, Debug, Default)]
+pub struct ConstraintSections {
+    open: Vec<(Section, usize)>, // 아직 끝나지 않은 구간과 시작 index
+    closed: Vec<(Vec<Section>, Range<usize>)>, // 구간의 경로 (바깥 구간부터)와 constraint 범위
+}
+
+impl ConstraintSections {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // 구간 시작. `exit`까지 만든 constraint가 이 구간에 속함
+    pub fn enter(&mut self, cs: &ConstraintSystemRef<F>, section: Section) {
+        self.open.push((section, cs.num_constraints()));
+    }
+
+    // 가장 안쪽 구간 끝
+    pub fn exit(&mut self, cs: &ConstraintSystemRef<F>) {
+        let path = self.open.iter().map(|(section, _)| *section).collect();
+        let (_, start) = self.open.pop().expect("exit matches an enter");
+        self.closed.push((path, start..cs.num_constraints()));
+    }
+
+    /// `index`번째 constraint가 속한 가장 안쪽 구간의 경로
+    pub fn path_of(&self, index: usize) -> Option<Vec<Section>> {
+        self.closed
+            .iter()
+            .filter(|(_, range)| range.contains(&index))
+            .max_by_key(|(path, _)| path.len())
+            .map(|(path, _)| path.clone())
+    }
+}
+
+/// 구간을 기록하면서 constraint를 만드는 circuit
+/// `ConstraintSynthesizer`의 `generate_constraints`는 기록하지 않는 `ConstraintSections`로 이 함수를 호출
+pub trait SectionedCircuit: ConstraintSynthesizer<F> {
+    fn generate_sectioned_constraints(
+        self,
+        cs: ConstraintSystemRef<F>,
+        sections: &mut ConstraintSections,
+    ) -> Result<(), SynthesisError>;
+}
+
+/// 만족되지 않는 첫 constraint
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Unsatisfied {
+    pub index: usize,       // constraint index (0부터)
+    pub path: Vec<Section>, // constraint가 속한 구간의 경로 (바깥 구간부터). 구간 밖이면 비어 있음
+}
+
+impl fmt::Display for Unsatisfied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            return write!(f, "constraint {}", self.index);
+        }
+        let path: Vec<String> = self.path.iter().map(Section::to_string).collect();
+        write!(f, "{} (constraint {})", path.join(" / "), self.index)
+    }
+}
+
+/// circuit을 ConstraintSystem에 합성하여 만족되지 않는 첫 constraint와 그 구간을 찾음. 모두 만족하면 `None`
+/// 증명 생성 전에 witness가 relation을 만족하는지 확인하는 디버깅용
+pub fn which_is_unsatisfied<C: SectionedCircuit>(
+    circuit: C,
+) -> Result<Option<Unsatisfied>, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    let mut sections = ConstraintSections::new();
+    circuit.generate_sectioned_constraints(cs.clone(), &mut sections)?;
+
+    Ok(first_unsatisfied(&cs)?.map(|index| Unsatisfied {
+        index,
+        path: sections.path_of(index).unwrap_or_default(),
+    }))
+}
+
+// constraint 행렬로 각 constraint의 <A, z> * <B, z> = <C, z>를 직접 계산하여 만족되지 않는 첫 index를 찾음
+// z = (instance, witness). `ConstraintSystem::which_is_unsatisfied`의 문자열은 tracing 설정에 따라 형식이 달라짐
+fn first_unsatisfied(cs: &ConstraintSystemRef<F>) -> Result<Option<usize>, SynthesisError> {
+    // 행렬을 만들기 전에 linear combination을 constraint에 inline. constraint 수와 순서는 바뀌지 않음
+    cs.finalize();
+    let matrices = cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)?;
+    let cs = cs.borrow().ok_or(SynthesisError::AssignmentMissing)?;
+    let z: Vec<F> = cs
+        .instance_assignment
+        .iter()
+        .chain(&cs.witness_assignment)
+        .copied()
+        .collect();
+    if z.len() != matrices.num_instance_variables + matrices.num_witness_variables {
+        return Err(SynthesisError::AssignmentMissing);
+    }
+
+    let evaluate = |row: &[(F, usize)]| -> F { row.iter().map(|(coeff, i)| *coeff * z[*i]).sum() };
+    Ok((0..matrices.num_constraints).find(|&index| {
+        evaluate(&matrices.a[index]) * evaluate(&matrices.b[index]) != evaluate(&matrices.c[index])
+    }))
+}
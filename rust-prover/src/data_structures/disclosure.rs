@@ -0,0 +1,139 @@
+use ark_r1cs_std::{R1CSVar, alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    F,
+    data_structures::{
+        credential::{Credential, CredentialVar},
+        issuer_set::issuer_id_field,
+        schema::{Schema, SchemaError, Value},
+    },
+    error::Error,
+};
+
+/// Holder가 증명에서 공개하는 credential 속성 목록
+/// 공개한 값은 issuer_id, schema 순서의 속성 순서로 public input이 되고 나머지 속성은 witness로 남음
+/// 공개할 속성이 circuit의 구조를 결정하므로 setup에도 같은 `Disclosure`를 사용
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Disclosure {
+    issuer_id: bool,
+    attributes: Vec<String>,
+}
+
+/// Holder가 증명과 함께 제출하는 공개 값
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisclosedValues {
+    pub issuer_id: Option<[u8; 32]>,
+    pub attributes: Vec<(String, Value)>, // schema 순서
+}
+
+impl Disclosure {
+    // 아무것도 공개하지 않음
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn with_issuer_id(mut self) -> Self {
+        self.issuer_id = true;
+        self
+    }
+
+    pub fn with_attribute(mut self, name: &str) -> Self {
+        self.attributes.push(name.to_string());
+        self
+    }
+
+    /// 공개할 속성이 schema에 한 번씩 선언되어 있는지 확인
+    pub fn check(&self, schema: &Schema) -> Result<(), SchemaError> {
+        for (i, name) in self.attributes.iter().enumerate() {
+            if schema.index_of(name).is_none() {
+                return Err(SchemaError::UnknownAttribute);
+            }
+            if self.attributes[..i].contains(name) {
+                return Err(SchemaError::DuplicateAttribute);
+            }
+        }
+        Ok(())
+    }
+
+    // 공개할 속성의 schema index. schema 순서로 정렬
+    fn indices(&self, schema: &Schema) -> Result<Vec<usize>, SchemaError> {
+        self.check(schema)?;
+        let mut indices: Vec<usize> = self
+            .attributes
+            .iter()
+            .filter_map(|name| schema.index_of(name))
+            .collect();
+        indices.sort();
+        Ok(indices)
+    }
+
+    /// Holder: credential에서 공개할 값을 꺼냄
+    pub fn reveal(&self, credential: &Credential) -> Result<DisclosedValues, SchemaError> {
+        let attributes = self
+            .indices(&credential.schema)?
+            .into_iter()
+            .map(|i| {
+                let name = &credential.schema.attributes()[i].0;
+                let value = credential
+                    .attribute(name)
+                    .expect("index comes from the credential's schema");
+                (name.clone(), value.clone())
+            })
+            .collect();
+        Ok(DisclosedValues {
+            issuer_id: self.issuer_id.then_some(credential.issuer_id),
+            attributes,
+        })
+    }
+
+    /// Verifier: Holder가 제출한 공개 값으로 circuit의 `generate_constraints`와 같은 순서의 public input을 만듦
+    /// 요청한 값이 빠졌거나 요청하지 않은 값이 있으면 `DisclosureMismatch`, 타입이 schema와 다르면 `Schema` 오류
+    pub fn public_inputs(
+        &self,
+        schema: &Schema,
+        disclosed: &DisclosedValues,
+    ) -> Result<Vec<F>, Error> {
+        let indices = self.indices(schema)?;
+        if self.issuer_id != disclosed.issuer_id.is_some()
+            || indices.len() != disclosed.attributes.len()
+        {
+            return Err(Error::DisclosureMismatch);
+        }
+
+        let mut inputs: Vec<F> = disclosed.issuer_id.iter().map(issuer_id_field).collect();
+        for (i, (name, value)) in indices.into_iter().zip(&disclosed.attributes) {
+            let (expected_name, attribute_type) = &schema.attributes()[i];
+            if name != expected_name {
+                return Err(Error::DisclosureMismatch);
+            }
+            inputs.push(attribute_type.encode(value)?);
+        }
+        Ok(inputs)
+    }
+
+    /// 공개할 값을 public input으로 할당하고 credential의 값과 같은지 확인
+    pub fn generate_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        credential: &CredentialVar,
+    ) -> Result<(), SynthesisError> {
+        let mut disclosed_vars = Vec::new();
+        if self.issuer_id {
+            disclosed_vars.push(credential.issuer_id_field()?);
+        }
+        for i in self
+            .indices(&credential.schema)
+            .map_err(|_| SynthesisError::Unsatisfiable)?
+        {
+            disclosed_vars.push(credential.attributes[i].clone());
+        }
+
+        for disclosed_var in disclosed_vars {
+            let input_var = FpVar::new_input(cs.clone(), || disclosed_var.value())?;
+            input_var.enforce_equal(&disclosed_var)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+use std::{borrow::Borrow, fmt::Debug, sync::OnceLock};
+
+use ark_crypto_primitives::{
+    crh::{
+        CRHScheme, CRHSchemeGadget, TwoToOneCRHScheme, TwoToOneCRHSchemeGadget,
+        pedersen::{self, Window, constraints::CRHParametersVar as PedersenParametersVar},
+        poseidon::constraints::CRHParametersVar as PoseidonParametersVar,
+        sha256::constraints::{DigestVar, UnitVar},
+    },
+    merkle_tree::{Config, constraints::ConfigGadget},
+    sponge::poseidon::PoseidonConfig,
+};
+use ark_ed_on_bn254::{EdwardsAffine, EdwardsProjective, constraints::EdwardsVar};
+use ark_ff::Zero;
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, uint8::UInt8};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use rand::{SeedableRng, rngs::StdRng};
+
+use crate::{
+    F, Sha256Digest,
+    data_structures::{
+        credential::{Credential, CredentialVar},
+        merkle_tree::{
+            PedersenCredentialTreeConfig, PedersenCredentialTreeConfigVar, PedersenLeafWindow,
+            PedersenWindow, PoseidonCredentialTreeConfig, PoseidonCredentialTreeConfigVar,
+            Sha256CredentialTreeConfig, Sha256CredentialTreeConfigVar,
+        },
+    },
+    utils::{
+        poseidon::poseidon_config,
+        utils::{DigestEncoding, digest_input_var, digest_to_field_elements},
+    },
+};
+
+/// circuit 안에서 사용하는 leaf 해시 파라미터
+pub type LeafParamVar<H> = <<<H as CredentialHasher>::TreeConfigVar as ConfigGadget<
+    <H as CredentialHasher>::TreeConfig,
+    F,
+>>::LeafHash as CRHSchemeGadget<
+    <<H as CredentialHasher>::TreeConfig as Config>::LeafHash,
+    F,
+>>::ParametersVar;
+
+/// circuit 안에서 사용하는 inner node 해시 파라미터
+pub type TwoToOneParamVar<H> = <<<H as CredentialHasher>::TreeConfigVar as ConfigGadget<
+    <H as CredentialHasher>::TreeConfig,
+    F,
+>>::TwoToOneHash as TwoToOneCRHSchemeGadget<
+    <<H as CredentialHasher>::TreeConfig as Config>::TwoToOneHash,
+    F,
+>>::ParametersVar;
+
+/// credential 해시(commitment) 방식. native 해시와 circuit 안의 gadget을 한 쌍으로 묶음
+/// Issuer가 publish하는 해시(`hash`)와 circuit이 계산하는 해시(`hash_var`)는
+/// 모두 credential tree의 leaf hash에 `encode` / `encode_var`로 직렬화한 credential을 넣어 계산하므로
+/// 두 직렬화만 일치하면 항상 같은 값이 나옴
+pub trait CredentialHasher: Sized {
+    /// credential 해시이자 credential tree의 node
+    type Digest: Clone + PartialEq + Debug;
+    /// circuit 안에서 사용하는 `Digest`
+    type DigestVar: AllocVar<Self::Digest, F> + EqGadget<F>;
+
+    /// leaf / inner node 해시 파라미터. 한 번만 생성하여 계속 사용
+    type LeafParams: 'static;
+    type TwoToOneParams: 'static;
+
+    type TreeConfig: Config<
+            LeafDigest = Self::Digest,
+            InnerDigest = Self::Digest,
+            LeafHash: CRHScheme<Parameters = Self::LeafParams>,
+            TwoToOneHash: TwoToOneCRHScheme<Parameters = Self::TwoToOneParams>,
+        >;
+    type TreeConfigVar: ConfigGadget<
+            Self::TreeConfig,
+            F,
+            LeafDigest = Self::DigestVar,
+            InnerDigest = Self::DigestVar,
+        >;
+
+    /// 직렬화한 credential (leaf hash의 입력)
+    type Leaf: Borrow<<Self::TreeConfig as Config>::Leaf>;
+    /// circuit 안에서 직렬화한 credential
+    type LeafVar: Borrow<<Self::TreeConfigVar as ConfigGadget<Self::TreeConfig, F>>::Leaf>;
+
+    fn leaf_params() -> &'static Self::LeafParams;
+    fn two_to_one_params() -> &'static Self::TwoToOneParams;
+    fn leaf_params_var(cs: ConstraintSystemRef<F>) -> Result<LeafParamVar<Self>, SynthesisError>;
+    fn two_to_one_params_var(
+        cs: ConstraintSystemRef<F>,
+    ) -> Result<TwoToOneParamVar<Self>, SynthesisError>;
+
+    /// credential을 leaf hash의 입력으로 직렬화
+    fn encode(credential: &Credential) -> Self::Leaf;
+    /// `encode`와 동일한 순서로 circuit 안에서 credential 직렬화
+    fn encode_var(credential: &CredentialVar) -> Result<Self::LeafVar, SynthesisError>;
+
+    /// credential tree의 빈 leaf. preimage를 알 수 없는 값이어야 함
+    fn empty_leaf() -> Self::Digest;
+
+    /// credential root를 public input으로 변환
+    /// circuit의 `root_input_var`와 동일한 순서를 사용해야 함
+    fn root_to_field_elements(root: &Self::Digest, encoding: DigestEncoding) -> Vec<F>;
+    /// credential root를 public input으로 할당
+    fn root_input_var(
+        cs: impl Into<Namespace<F>>,
+        root: &Self::Digest,
+        encoding: DigestEncoding,
+    ) -> Result<Self::DigestVar, SynthesisError>;
+
+    /// credential 해시 계산. Issuer가 publish하는 값
+    fn hash(credential: &Credential) -> Self::Digest {
+        <<Self::TreeConfig as Config>::LeafHash as CRHScheme>::evaluate(
+            Self::leaf_params(),
+            Self::encode(credential),
+        )
+        .expect("credential encoding fits the leaf hash input")
+    }
+
+    /// circuit 안에서 credential 해시 계산
+    fn hash_var(
+        params: &LeafParamVar<Self>,
+        credential: &CredentialVar,
+    ) -> Result<Self::DigestVar, SynthesisError> {
+        <<Self::TreeConfigVar as ConfigGadget<Self::TreeConfig, F>>::LeafHash as CRHSchemeGadget<
+            _,
+            F,
+        >>::evaluate(params, Self::encode_var(credential)?.borrow())
+    }
+}
+
+/// SHA256 commitment. circuit 안에서 credential 하나의 해시에 수만 개의 constraint 필요
+pub struct Sha256Hasher;
+
+impl CredentialHasher for Sha256Hasher {
+    type Digest = Sha256Digest;
+    type DigestVar = DigestVar<F>;
+    type LeafParams = ();
+    type TwoToOneParams = ();
+    type TreeConfig = Sha256CredentialTreeConfig;
+    type TreeConfigVar = Sha256CredentialTreeConfigVar;
+    type Leaf = Vec<u8>;
+    type LeafVar = Vec<UInt8<F>>;
+
+    fn leaf_params() -> &'static () {
+        &()
+    }
+
+    fn two_to_one_params() -> &'static () {
+        &()
+    }
+
+    fn leaf_params_var(_: ConstraintSystemRef<F>) -> Result<UnitVar<F>, SynthesisError> {
+        Ok(UnitVar::default())
+    }
+
+    fn two_to_one_params_var(_: ConstraintSystemRef<F>) -> Result<UnitVar<F>, SynthesisError> {
+        Ok(UnitVar::default())
+    }
+
+    fn encode(credential: &Credential) -> Vec<u8> {
+        credential.to_bytes()
+    }
+
+    fn encode_var(credential: &CredentialVar) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        credential.to_bytes()
+    }
+
+    fn empty_leaf() -> Sha256Digest {
+        vec![0u8; 32]
+    }
+
+    fn root_to_field_elements(root: &Sha256Digest, encoding: DigestEncoding) -> Vec<F> {
+        digest_to_field_elements(root, encoding)
+    }
+
+    fn root_input_var(
+        cs: impl Into<Namespace<F>>,
+        root: &Sha256Digest,
+        encoding: DigestEncoding,
+    ) -> Result<DigestVar<F>, SynthesisError> {
+        digest_input_var(cs, root, encoding)
+    }
+}
+
+/// Poseidon commitment. field 원소 위에서 정의된 해시로 수백 개의 constraint로 충분
+pub struct PoseidonHasher;
+
+fn cached_poseidon_config() -> &'static PoseidonConfig<F> {
+    static CONFIG: OnceLock<PoseidonConfig<F>> = OnceLock::new();
+    CONFIG.get_or_init(poseidon_config)
+}
+
+impl CredentialHasher for PoseidonHasher {
+    type Digest = F;
+    type DigestVar = FpVar<F>;
+    type LeafParams = PoseidonConfig<F>;
+    type TwoToOneParams = PoseidonConfig<F>;
+    type TreeConfig = PoseidonCredentialTreeConfig;
+    type TreeConfigVar = PoseidonCredentialTreeConfigVar;
+    type Leaf = Vec<F>;
+    type LeafVar = Vec<FpVar<F>>;
+
+    fn leaf_params() -> &'static PoseidonConfig<F> {
+        cached_poseidon_config()
+    }
+
+    fn two_to_one_params() -> &'static PoseidonConfig<F> {
+        cached_poseidon_config()
+    }
+
+    fn leaf_params_var(
+        cs: ConstraintSystemRef<F>,
+    ) -> Result<PoseidonParametersVar<F>, SynthesisError> {
+        PoseidonParametersVar::new_constant(cs, cached_poseidon_config())
+    }
+
+    fn two_to_one_params_var(
+        cs: ConstraintSystemRef<F>,
+    ) -> Result<PoseidonParametersVar<F>, SynthesisError> {
+        PoseidonParametersVar::new_constant(cs, cached_poseidon_config())
+    }
+
+    fn encode(credential: &Credential) -> Vec<F> {
+        credential.to_field_elements()
+    }
+
+    fn encode_var(credential: &CredentialVar) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        credential.to_field_elements()
+    }
+
+    fn empty_leaf() -> F {
+        F::zero()
+    }
+
+    // Poseidon root는 그 자체가 하나의 field 원소
+    fn root_to_field_elements(root: &F, _: DigestEncoding) -> Vec<F> {
+        vec![*root]
+    }
+
+    fn root_input_var(
+        cs: impl Into<Namespace<F>>,
+        root: &F,
+        _: DigestEncoding,
+    ) -> Result<FpVar<F>, SynthesisError> {
+        FpVar::new_input(cs, || Ok(*root))
+    }
+}
+
+/// Pedersen commitment. BabyJubjub 위의 점으로 해시하며 SHA256보다 constraint 수가 훨씬 적음
+pub struct PedersenHasher;
+
+type PedersenParameters = pedersen::Parameters<EdwardsProjective>;
+
+// Pedersen 해시의 generator는 공개된 seed로 결정적으로 생성하므로 누구나 같은 값을 얻음
+// leaf와 inner node는 서로 다른 generator를 사용
+const PEDERSEN_LEAF_SEED: u64 = 0;
+const PEDERSEN_TWO_TO_ONE_SEED: u64 = 1;
+
+fn pedersen_parameters<W: Window>(seed: u64) -> PedersenParameters {
+    pedersen::CRH::<EdwardsProjective, W>::setup(&mut StdRng::seed_from_u64(seed))
+        .expect("Pedersen setup never fails")
+}
+
+impl CredentialHasher for PedersenHasher {
+    type Digest = EdwardsAffine;
+    type DigestVar = EdwardsVar;
+    type LeafParams = PedersenParameters;
+    type TwoToOneParams = PedersenParameters;
+    type TreeConfig = PedersenCredentialTreeConfig;
+    type TreeConfigVar = PedersenCredentialTreeConfigVar;
+    type Leaf = Vec<u8>;
+    type LeafVar = Vec<UInt8<F>>;
+
+    fn leaf_params() -> &'static PedersenParameters {
+        static PARAMS: OnceLock<PedersenParameters> = OnceLock::new();
+        PARAMS.get_or_init(|| pedersen_parameters::<PedersenLeafWindow>(PEDERSEN_LEAF_SEED))
+    }
+
+    fn two_to_one_params() -> &'static PedersenParameters {
+        static PARAMS: OnceLock<PedersenParameters> = OnceLock::new();
+        PARAMS.get_or_init(|| pedersen_parameters::<PedersenWindow>(PEDERSEN_TWO_TO_ONE_SEED))
+    }
+
+    fn leaf_params_var(
+        cs: ConstraintSystemRef<F>,
+    ) -> Result<PedersenParametersVar<EdwardsProjective, EdwardsVar>, SynthesisError> {
+        PedersenParametersVar::new_constant(cs, Self::leaf_params())
+    }
+
+    fn two_to_one_params_var(
+        cs: ConstraintSystemRef<F>,
+    ) -> Result<PedersenParametersVar<EdwardsProjective, EdwardsVar>, SynthesisError> {
+        PedersenParametersVar::new_constant(cs, Self::two_to_one_params())
+    }
+
+    fn encode(credential: &Credential) -> Vec<u8> {
+        credential.to_bytes()
+    }
+
+    fn encode_var(credential: &CredentialVar) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        credential.to_bytes()
+    }
+
+    // 항등원 (0, 1). 특정 입력의 해시가 항등원이 되려면 generator 사이의 이산로그 관계를 알아야 함
+    fn empty_leaf() -> EdwardsAffine {
+        EdwardsAffine::zero()
+    }
+
+    // Pedersen root는 곡선 위의 점 (x, y) 두 개의 field 원소
+    fn root_to_field_elements(root: &EdwardsAffine, _: DigestEncoding) -> Vec<F> {
+        vec![root.x, root.y]
+    }
+
+    fn root_input_var(
+        cs: impl Into<Namespace<F>>,
+        root: &EdwardsAffine,
+        _: DigestEncoding,
+    ) -> Result<EdwardsVar, SynthesisError> {
+        EdwardsVar::new_input(cs, || Ok(*root))
+    }
+}
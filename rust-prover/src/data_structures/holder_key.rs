@@ -0,0 +1,70 @@
+use ark_crypto_primitives::crh::{
+    CRHScheme, CRHSchemeGadget,
+    poseidon::{CRH as PoseidonCRH, constraints::CRHGadget as PoseidonCRHGadget},
+};
+use ark_ff::UniformRand;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    F,
+    data_structures::hasher::{CredentialHasher, PoseidonHasher},
+    utils::serialization::canonical,
+};
+
+// 공개 키 해시의 domain tag. nullifier 등 같은 Poseidon 파라미터를 쓰는 다른 해시와 값이 겹치지 않게 함
+const PUBLIC_KEY_DOMAIN: u64 = 1;
+
+/// Holder의 키 쌍. 공개 키는 비밀 키의 Poseidon 해시
+/// Issuer는 credential에 공개 키를 넣어 발급하고, circuit은 그 공개 키의 비밀 키를 아는지 확인하므로
+/// credential(randomness 포함)을 알아낸 사람도 비밀 키 없이는 증명할 수 없음
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HolderKey {
+    #[serde(with = "canonical")]
+    secret_key: F,
+    #[serde(with = "canonical")]
+    pub public_key: F,
+}
+
+impl HolderKey {
+    pub fn new(secret_key: F) -> Self {
+        HolderKey {
+            secret_key,
+            public_key: public_key(secret_key),
+        }
+    }
+
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        Self::new(F::rand(rng))
+    }
+
+    pub fn secret_key(&self) -> F {
+        self.secret_key
+    }
+}
+
+/// 비밀 키에 대응하는 공개 키
+pub fn public_key(secret_key: F) -> F {
+    PoseidonCRH::<F>::evaluate(
+        PoseidonHasher::leaf_params(),
+        [F::from(PUBLIC_KEY_DOMAIN), secret_key],
+    )
+    .expect("poseidon accepts any number of field elements")
+}
+
+/// circuit 안에서 `public_key`와 동일하게 계산
+pub fn public_key_var(
+    cs: ConstraintSystemRef<F>,
+    secret_key: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    let params_var = PoseidonHasher::leaf_params_var(cs)?;
+    PoseidonCRHGadget::<F>::evaluate(
+        &params_var,
+        &[
+            FpVar::Constant(F::from(PUBLIC_KEY_DOMAIN)),
+            secret_key.clone(),
+        ],
+    )
+}
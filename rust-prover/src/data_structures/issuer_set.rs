@@ -0,0 +1,116 @@
+use ark_crypto_primitives::merkle_tree::{MerkleTree, Path, constraints::PathVar};
+use ark_ed_on_bn254::EdwardsAffine;
+use ark_ff::{PrimeField, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    F,
+    data_structures::{
+        hasher::{CredentialHasher, PoseidonHasher},
+        merkle_tree::{
+            PoseidonCredentialTreeConfig, PoseidonCredentialTreeConfigVar, tree_capacity,
+        },
+    },
+    error::Error,
+    utils::serialization::canonical,
+};
+
+pub const ISSUER_SET_TREE_HEIGHT: usize = 5; // issuer set tree의 높이. 최대 2^4 = 16명의 Issuer
+
+/// Verifier가 신뢰하는 Issuer들의 Merkle tree
+/// - leaf: `issuer_leaf` (Issuer id, 서명 공개 키와 revocation root). 빈 자리는 곡선 위의 점이 아닌 (0, 0)이라 증명에 쓸 수 없음
+/// - 해시: Poseidon. root는 field 원소 1개의 public input
+///
+/// Holder는 credential의 issuer_id와 서명을 검증한 공개 키, 증명에 사용한 revocation root의 leaf가 tree에 있음을 증명
+/// Issuer가 credential을 폐기하면 revocation root가 바뀌므로 Verifier는 현재 root로 issuer set을 다시 만들어야 함
+pub type IssuerSetTree = MerkleTree<PoseidonCredentialTreeConfig>;
+/// Issuer의 leaf에서 issuer set root까지의 Merkle path
+pub type IssuerPath = Path<PoseidonCredentialTreeConfig>;
+pub type IssuerPathVar = PathVar<PoseidonCredentialTreeConfig, F, PoseidonCredentialTreeConfigVar>;
+
+/// issuer set에 등록하는 Issuer의 id, credential 서명 공개 키와 revocation root
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustedIssuer {
+    pub id: [u8; 32],
+    #[serde(with = "canonical")]
+    pub public_key: EdwardsAffine,
+    #[serde(with = "canonical")]
+    pub revocation_root: F, // Issuer가 공개한 폐기 리스트의 root. 다른 Issuer의 root로 폐기 확인을 피할 수 없음
+}
+
+/// Verifier가 신뢰하는 Issuer 목록
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IssuerSet {
+    issuers: Vec<TrustedIssuer>,
+}
+
+/// issuer_id를 field 원소로 변환. `CredentialVar::issuer_id_field`와 같은 값
+/// (id는 `string_to_bytes`로 인코딩되어 마지막 바이트가 31 이하이므로 mod 연산으로 값이 바뀌지 않음)
+pub fn issuer_id_field(issuer_id: &[u8; 32]) -> F {
+    F::from_le_bytes_mod_order(issuer_id)
+}
+
+// Issuer의 leaf: (issuer_id, 공개 키 x, 공개 키 y, revocation root)
+pub fn issuer_leaf(issuer: &TrustedIssuer) -> [F; 4] {
+    [
+        issuer_id_field(&issuer.id),
+        issuer.public_key.x,
+        issuer.public_key.y,
+        issuer.revocation_root,
+    ]
+}
+
+impl IssuerSet {
+    // tree에 들어가는 개수까지만 등록 가능
+    pub fn new(issuers: Vec<TrustedIssuer>) -> Result<Self, Error> {
+        let capacity = tree_capacity(ISSUER_SET_TREE_HEIGHT);
+        if issuers.len() > capacity {
+            return Err(Error::IssuerSetFull { capacity });
+        }
+        Ok(IssuerSet { issuers })
+    }
+
+    pub fn issuers(&self) -> &[TrustedIssuer] {
+        &self.issuers
+    }
+
+    pub fn issuer(&self, issuer_id: &[u8; 32]) -> Option<&TrustedIssuer> {
+        self.issuers.iter().find(|issuer| issuer.id == *issuer_id)
+    }
+
+    fn tree(&self) -> Result<IssuerSetTree, Error> {
+        let mut leaves = vec![[F::zero(); 4]; tree_capacity(ISSUER_SET_TREE_HEIGHT)];
+        for (leaf, issuer) in leaves.iter_mut().zip(&self.issuers) {
+            *leaf = issuer_leaf(issuer);
+        }
+
+        IssuerSetTree::new(
+            PoseidonHasher::leaf_params(),
+            PoseidonHasher::two_to_one_params(),
+            leaves,
+        )
+        .map_err(Error::credential_tree)
+    }
+
+    /// Verifier가 공개하는 issuer set의 root. circuit의 public input으로 사용
+    pub fn root(&self) -> Result<F, Error> {
+        Ok(self.tree()?.root())
+    }
+
+    /// `issuer_id` Issuer의 공개 키와 leaf에서 root까지의 Merkle path
+    pub fn membership_path(
+        &self,
+        issuer_id: &[u8; 32],
+    ) -> Result<(EdwardsAffine, IssuerPath), Error> {
+        let index = self
+            .issuers
+            .iter()
+            .position(|issuer| issuer.id == *issuer_id)
+            .ok_or(Error::UntrustedIssuer)?;
+        let path = self
+            .tree()?
+            .generate_proof(index)
+            .map_err(Error::credential_tree)?;
+        Ok((self.issuers[index].public_key, path))
+    }
+}
@@ -0,0 +1,197 @@
+use ark_crypto_primitives::{
+    Error,
+    crh::{
+        pedersen::{
+            CRH as PedersenCRH, TwoToOneCRH as PedersenTwoToOneCRH, Window,
+            constraints::{
+                CRHGadget as PedersenCRHGadget, TwoToOneCRHGadget as PedersenTwoToOneCRHGadget,
+            },
+        },
+        poseidon::{
+            CRH as PoseidonCRH, TwoToOneCRH as PoseidonTwoToOneCRH,
+            constraints::{
+                CRHGadget as PoseidonCRHGadget, TwoToOneCRHGadget as PoseidonTwoToOneCRHGadget,
+            },
+        },
+        sha256::{
+            Sha256,
+            constraints::{DigestVar, Sha256Gadget},
+        },
+    },
+    merkle_tree::{
+        ByteDigestConverter, Config, DigestConverter, IdentityDigestConverter, MerkleTree, Path,
+        constraints::{BytesVarDigestConverter, ConfigGadget, DigestVarConverter, PathVar},
+    },
+};
+use ark_ed_on_bn254::{EdwardsAffine, EdwardsProjective, constraints::EdwardsVar};
+use ark_r1cs_std::{fields::fp::FpVar, uint8::UInt8};
+use ark_relations::r1cs::SynthesisError;
+
+use crate::{F, Sha256Digest, data_structures::hasher::CredentialHasher};
+
+/// Issuer가 발급한 credential의 SHA256 해시들로 만드는 Merkle tree의 설정
+/// - leaf: credential을 직렬화한 바이트 (leaf hash = credential의 SHA256 해시)
+/// - inner node: 두 자식 노드 해시를 이어붙인 64바이트의 SHA256 해시
+pub struct Sha256CredentialTreeConfig;
+
+impl Config for Sha256CredentialTreeConfig {
+    type Leaf = [u8];
+    type LeafDigest = Sha256Digest;
+    type LeafInnerDigestConverter = Sha256DigestConverter;
+    type InnerDigest = Sha256Digest;
+    type LeafHash = Sha256;
+    type TwoToOneHash = Sha256;
+}
+
+/// circuit 안에서 사용하는 `Sha256CredentialTreeConfig`
+pub struct Sha256CredentialTreeConfigVar;
+
+impl ConfigGadget<Sha256CredentialTreeConfig, F> for Sha256CredentialTreeConfigVar {
+    type Leaf = [UInt8<F>];
+    type LeafDigest = DigestVar<F>;
+    type LeafInnerConverter = Sha256DigestConverter;
+    type InnerDigest = DigestVar<F>;
+    type LeafHash = Sha256Gadget<F>;
+    type TwoToOneHash = Sha256Gadget<F>;
+}
+
+/// leaf의 SHA256 해시를 길이 정보 없이 32바이트 그대로 inner node의 입력으로 사용
+/// (ark의 `ByteDigestConverter`는 길이를 앞에 붙여 직렬화하므로 circuit과 결과가 달라짐)
+pub struct Sha256DigestConverter;
+
+impl DigestConverter<Sha256Digest, [u8]> for Sha256DigestConverter {
+    type TargetType = Sha256Digest;
+
+    fn convert(item: Sha256Digest) -> Result<Self::TargetType, Error> {
+        Ok(item)
+    }
+}
+
+impl DigestVarConverter<DigestVar<F>, [UInt8<F>]> for Sha256DigestConverter {
+    type TargetType = Vec<UInt8<F>>;
+
+    fn convert(from: DigestVar<F>) -> Result<Self::TargetType, SynthesisError> {
+        Ok(from.0)
+    }
+}
+
+/// Issuer가 발급한 credential의 Poseidon 해시들로 만드는 Merkle tree의 설정
+/// - leaf: credential을 직렬화한 field 원소 (leaf hash = credential의 Poseidon 해시)
+/// - inner node: 두 자식 노드의 Poseidon 해시
+pub struct PoseidonCredentialTreeConfig;
+
+impl Config for PoseidonCredentialTreeConfig {
+    type Leaf = [F];
+    type LeafDigest = F;
+    type LeafInnerDigestConverter = IdentityDigestConverter<F>;
+    type InnerDigest = F;
+    type LeafHash = PoseidonCRH<F>;
+    type TwoToOneHash = PoseidonTwoToOneCRH<F>;
+}
+
+/// circuit 안에서 사용하는 `PoseidonCredentialTreeConfig`
+pub struct PoseidonCredentialTreeConfigVar;
+
+impl ConfigGadget<PoseidonCredentialTreeConfig, F> for PoseidonCredentialTreeConfigVar {
+    type Leaf = [FpVar<F>];
+    type LeafDigest = FpVar<F>;
+    type LeafInnerConverter = IdentityDigestConverter<FpVar<F>>;
+    type InnerDigest = FpVar<F>;
+    type LeafHash = PoseidonCRHGadget<F>;
+    type TwoToOneHash = PoseidonTwoToOneCRHGadget<F>;
+}
+
+/// inner node Pedersen 해시의 입력 크기: 4비트 window 256개 = 1024비트 (128바이트)
+/// 두 자식 노드(좌표 32바이트 x 2 x 2)가 들어감
+#[derive(Clone)]
+pub struct PedersenWindow;
+
+impl Window for PedersenWindow {
+    const WINDOW_SIZE: usize = 4;
+    const NUM_WINDOWS: usize = 256;
+}
+
+/// leaf Pedersen 해시의 입력 크기: 4비트 window 768개 = 3072비트 (384바이트)
+/// 속성이 `MAX_SCHEMA_ATTRIBUTES`개인 credential 직렬화(32바이트 x 12)가 들어감
+#[derive(Clone)]
+pub struct PedersenLeafWindow;
+
+impl Window for PedersenLeafWindow {
+    const WINDOW_SIZE: usize = 4;
+    const NUM_WINDOWS: usize = 768;
+}
+
+/// Issuer가 발급한 credential의 Pedersen 해시들로 만드는 Merkle tree의 설정
+/// - leaf: credential을 직렬화한 바이트 (leaf hash = BabyJubjub 위의 점)
+/// - inner node: 두 자식 노드의 좌표 (x, y)를 Little-endian 바이트로 이어붙인 값의 Pedersen 해시
+pub struct PedersenCredentialTreeConfig;
+
+impl Config for PedersenCredentialTreeConfig {
+    type Leaf = [u8];
+    type LeafDigest = EdwardsAffine;
+    type LeafInnerDigestConverter = ByteDigestConverter<EdwardsAffine>;
+    type InnerDigest = EdwardsAffine;
+    type LeafHash = PedersenCRH<EdwardsProjective, PedersenLeafWindow>;
+    type TwoToOneHash = PedersenTwoToOneCRH<EdwardsProjective, PedersenWindow>;
+}
+
+/// circuit 안에서 사용하는 `PedersenCredentialTreeConfig`
+pub struct PedersenCredentialTreeConfigVar;
+
+impl ConfigGadget<PedersenCredentialTreeConfig, F> for PedersenCredentialTreeConfigVar {
+    type Leaf = [UInt8<F>];
+    type LeafDigest = EdwardsVar;
+    type LeafInnerConverter = BytesVarDigestConverter<EdwardsVar, F>;
+    type InnerDigest = EdwardsVar;
+    type LeafHash = PedersenCRHGadget<EdwardsProjective, EdwardsVar, PedersenLeafWindow>;
+    type TwoToOneHash = PedersenTwoToOneCRHGadget<EdwardsProjective, EdwardsVar, PedersenWindow>;
+}
+
+pub type CredentialTree<H> = MerkleTree<<H as CredentialHasher>::TreeConfig>;
+/// credential 해시에서 root까지의 Merkle path
+pub type CredentialPath<H> = Path<<H as CredentialHasher>::TreeConfig>;
+pub type CredentialPathVar<H> =
+    PathVar<<H as CredentialHasher>::TreeConfig, F, <H as CredentialHasher>::TreeConfigVar>;
+
+/// 높이가 `tree_height`인 tree에 담을 수 있는 credential의 최대 개수
+pub fn tree_capacity(tree_height: usize) -> usize {
+    1 << (tree_height - 1)
+}
+
+/// Issuer가 publish한 credential 해시 리스트로 Merkle tree 생성
+/// 빈 leaf는 preimage를 알 수 없는 `H::empty_leaf()`로 채움
+pub fn build_credential_tree<H: CredentialHasher>(
+    tree_height: usize,
+    hashed_credentials: &[H::Digest],
+) -> Result<CredentialTree<H>, Error> {
+    let capacity = tree_capacity(tree_height);
+    if hashed_credentials.len() > capacity {
+        return Err(format!(
+            "{} credentials do not fit in a tree of height {}",
+            hashed_credentials.len(),
+            tree_height
+        )
+        .into());
+    }
+
+    let mut leaves = hashed_credentials.to_vec();
+    leaves.resize(capacity, H::empty_leaf());
+    CredentialTree::<H>::new_with_leaf_digest(H::leaf_params(), H::two_to_one_params(), leaves)
+}
+
+/// publish된 credential 해시 리스트의 Merkle root
+pub fn credential_root<H: CredentialHasher>(
+    tree_height: usize,
+    hashed_credentials: &[H::Digest],
+) -> Result<H::Digest, Error> {
+    Ok(build_credential_tree::<H>(tree_height, hashed_credentials)?.root())
+}
+
+/// publish된 credential 해시 리스트에서 `index`번째 credential의 Merkle path
+pub fn membership_path<H: CredentialHasher>(
+    tree_height: usize,
+    hashed_credentials: &[H::Digest],
+    index: usize,
+) -> Result<CredentialPath<H>, Error> {
+    build_credential_tree::<H>(tree_height, hashed_credentials)?.generate_proof(index)
+}
@@ -1,2 +1,19 @@
+pub mod challenge;
 pub mod circuit;
 pub mod credential;
+pub mod date;
+pub mod diagnostics;
+pub mod disclosure;
+pub mod hasher;
+pub mod holder_key;
+pub mod issuer_set;
+pub mod merkle_tree;
+pub mod multi_credential;
+pub mod nullifier;
+pub mod policy;
+pub mod predicate;
+pub mod presentation;
+pub mod revocation;
+pub mod schema;
+pub mod signature;
+pub mod wallet;
@@ -0,0 +1,156 @@
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::{
+    ns,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError},
+};
+
+use crate::{
+    F,
+    data_structures::{
+        challenge,
+        circuit::{CredentialClaim, IssuanceMode},
+        credential::CredentialEncoding,
+        date::Date,
+        diagnostics::{ConstraintSections, Section, SectionedCircuit, section},
+        disclosure::Disclosure,
+        hasher::CredentialHasher,
+        holder_key::public_key_var,
+        nullifier,
+        predicate::Predicate,
+        schema::Schema,
+    },
+};
+
+/// 한 Holder가 가진 여러 credential(서로 다른 Issuer의 credential도 가능)로 한 번에 증명하는 circuit
+/// 예: 정부 신분증 credential로 나이, 동호회 credential로 회원 등급
+/// 모든 credential의 Holder 공개 키가 한 비밀 키로 계산한 공개 키와 같아야 하므로 다른 Holder의 credential을 섞을 수 없음
+///
+/// public input 순서: 기준 날짜, credential마다 (조건식의 값, 공개한 속성 값, `Issuance`의 값, revocation root),
+/// scope, challenge, credential마다 challenge binding, nullifier
+pub struct MultiCredentialCircuit<H: CredentialHasher> {
+    // public input
+    pub today: Date,
+    pub claims: Vec<CredentialClaim<H>>, // 각 credential의 조건식은 모두 만족해야 함 (and)
+    pub scope: F,
+    pub challenge: F,
+
+    // witness
+    pub holder_secret_key: F, // 모든 credential에 들어 있는 Holder 공개 키의 비밀 키
+}
+
+impl<H: CredentialHasher> Clone for MultiCredentialCircuit<H> {
+    fn clone(&self) -> Self {
+        MultiCredentialCircuit {
+            today: self.today,
+            claims: self.claims.clone(),
+            scope: self.scope,
+            challenge: self.challenge,
+            holder_secret_key: self.holder_secret_key,
+        }
+    }
+}
+
+impl<H: CredentialHasher> MultiCredentialCircuit<H> {
+    // 증명과 함께 제출하는 nullifier. credential 개수와 관계없이 Holder와 scope로 결정
+    pub fn nullifier(&self) -> F {
+        nullifier::nullifier(self.holder_secret_key, self.scope)
+    }
+
+    // 증명과 함께 제출하는 credential마다의 challenge binding
+    pub fn challenge_bindings(&self) -> Vec<F> {
+        self.claims
+            .iter()
+            .map(|claim| {
+                challenge::challenge_binding(
+                    self.holder_secret_key,
+                    &claim.credential,
+                    self.challenge,
+                )
+            })
+            .collect()
+    }
+}
+
+impl<H: CredentialHasher> ConstraintSynthesizer<F> for MultiCredentialCircuit<H> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> ark_relations::r1cs::Result<()> {
+        self.generate_sectioned_constraints(cs, &mut ConstraintSections::new())
+    }
+}
+
+impl<H: CredentialHasher> SectionedCircuit for MultiCredentialCircuit<H> {
+    fn generate_sectioned_constraints(
+        self,
+        cs: ConstraintSystemRef<F>,
+        sections: &mut ConstraintSections,
+    ) -> Result<(), SynthesisError> {
+        // -------------------- public input 할당 --------------------
+        let nullifier = self.nullifier();
+        let challenge_bindings = self.challenge_bindings();
+        let today_var = FpVar::new_input(cs.clone(), || Ok(self.today.to_field()))?;
+
+        // credential마다 발급, 폐기, 유효 기간, 조건식, 공개 값 확인
+        // 구간 경로는 "claim {index} / ..."
+        let mut credential_vars = Vec::new();
+        for (index, claim) in self.claims.into_iter().enumerate() {
+            let _namespace = ns!(cs, "claim");
+            sections.enter(&cs, Section::Claim(index));
+            let credential_var = claim.generate_constraints(cs.clone(), &today_var, sections);
+            sections.exit(&cs);
+            credential_vars.push(credential_var?);
+        }
+
+        let scope_var = FpVar::new_input(cs.clone(), || Ok(self.scope))?;
+        let challenge_var = FpVar::new_input(cs.clone(), || Ok(self.challenge))?;
+        let challenge_binding_vars = challenge_bindings
+            .into_iter()
+            .map(|binding| FpVar::new_input(cs.clone(), || Ok(binding)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let nullifier_var = FpVar::new_input(cs.clone(), || Ok(nullifier))?;
+
+        // -------------------- witness 할당 --------------------
+        let holder_secret_key_var = FpVar::new_witness(cs.clone(), || Ok(self.holder_secret_key))?;
+
+        // -------------------- constraints --------------------
+        // 모든 credential이 같은 Holder의 것인지 확인
+        // 비밀 키로 계산한 공개 키가 모든 credential의 Holder 공개 키와 같아야 함
+        section!(sections, cs, Section::HolderKey, {
+            let holder_public_key_var = public_key_var(cs.clone(), &holder_secret_key_var)?;
+            credential_vars.iter().try_for_each(|credential_var| {
+                holder_public_key_var.enforce_equal(&credential_var.holder_public_key)
+            })
+        })?;
+
+        // challenge가 다른 증명 요청에는 증명을 재사용할 수 없음
+        section!(sections, cs, Section::ChallengeBinding, {
+            credential_vars
+                .iter()
+                .zip(&challenge_binding_vars)
+                .try_for_each(|(credential_var, challenge_binding_var)| {
+                    let expected_challenge_binding_var = challenge::challenge_binding_var(
+                        cs.clone(),
+                        &holder_secret_key_var,
+                        credential_var,
+                        &challenge_var,
+                    )?;
+                    challenge_binding_var.enforce_equal(&expected_challenge_binding_var)
+                })
+        })?;
+
+        section!(sections, cs, Section::Nullifier, {
+            let expected_nullifier_var =
+                nullifier::nullifier_var(cs.clone(), &holder_secret_key_var, &scope_var)?;
+            nullifier_var.enforce_equal(&expected_nullifier_var)
+        })
+    }
+}
+
+/// setup에서 circuit의 구조를 결정하는 credential 하나의 모양
+/// 발급 확인 방식, schema, 조건식의 모양, 공개할 속성, credential 할당 방식. 조건식과 공개 값은 public input이므로 값은 setup에 영향을 주지 않음
+#[derive(Clone, Debug)]
+pub struct ClaimShape {
+    pub mode: IssuanceMode,
+    pub schema: Schema,
+    pub predicate: Predicate,
+    pub disclosure: Disclosure,
+    pub encoding: CredentialEncoding,
+}
@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+
+use ark_crypto_primitives::crh::{
+    CRHScheme, CRHSchemeGadget,
+    poseidon::{CRH as PoseidonCRH, constraints::CRHGadget as PoseidonCRHGadget},
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+
+use crate::{
+    F,
+    data_structures::hasher::{CredentialHasher, PoseidonHasher},
+    error::Error,
+};
+
+/// 증명을 받는 Verifier(또는 Verifier가 정한 범위)를 나타내는 field 원소
+/// 같은 Holder라도 scope가 다르면 nullifier가 달라 Verifier끼리 Holder를 연결할 수 없음
+pub fn scope_from_id(id: &[u8; 32]) -> F {
+    F::from_le_bytes_mod_order(id)
+}
+
+// nullifier 해시의 domain tag. Holder 공개 키 해시와 값이 겹치지 않게 함
+const NULLIFIER_DOMAIN: u64 = 2;
+
+/// Holder 비밀 키와 scope의 Poseidon 해시
+/// 같은 Holder가 같은 scope에 증명하면 항상 같은 값이 나오므로 Verifier가 중복 제출을 거부할 수 있음
+pub fn nullifier(secret_key: F, scope: F) -> F {
+    PoseidonCRH::<F>::evaluate(
+        PoseidonHasher::leaf_params(),
+        [F::from(NULLIFIER_DOMAIN), secret_key, scope],
+    )
+    .expect("poseidon accepts any number of field elements")
+}
+
+/// circuit 안에서 `nullifier`와 동일하게 계산
+pub fn nullifier_var(
+    cs: ConstraintSystemRef<F>,
+    secret_key: &FpVar<F>,
+    scope: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    let params_var = PoseidonHasher::leaf_params_var(cs)?;
+    PoseidonCRHGadget::<F>::evaluate(
+        &params_var,
+        &[
+            FpVar::Constant(F::from(NULLIFIER_DOMAIN)),
+            secret_key.clone(),
+            scope.clone(),
+        ],
+    )
+}
+
+/// Verifier가 이미 받은 nullifier 목록
+#[derive(Clone, Debug, Default)]
+pub struct NullifierStore {
+    seen: HashSet<F>,
+}
+
+impl NullifierStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains(&self, nullifier: &F) -> bool {
+        self.seen.contains(nullifier)
+    }
+
+    // 처음 받은 nullifier만 기록. 이미 받은 nullifier면 오류
+    pub fn insert(&mut self, nullifier: F) -> Result<(), Error> {
+        if !self.seen.insert(nullifier) {
+            return Err(Error::NullifierReused);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{
+    F,
+    data_structures::{
+        circuit::PublicIssuance,
+        date::Date,
+        disclosure::{DisclosedValues, Disclosure},
+        hasher::CredentialHasher,
+        predicate::Predicate,
+        schema::Schema,
+    },
+    error::Error,
+};
+
+/// Verifier가 증명 요청마다 정하는 조건. `AgeCircuit`의 public input 중 Verifier가 정하는 값
+#[derive(Clone, Debug)]
+pub struct Policy {
+    pub today: Date,            // 조건식과 유효 기간의 기준 날짜
+    pub schema: Schema,         // credential의 schema
+    pub predicate: Predicate,   // 조건식. 비교하는 값들이 public input
+    pub disclosure: Disclosure, // 공개를 요청하는 속성
+    pub challenge: F,           // 이번 증명 요청에 발급한 challenge
+}
+
+/// Holder가 증명과 함께 제출하는 값
+#[derive(Clone, Debug)]
+pub struct Submitted {
+    pub disclosed: DisclosedValues, // 공개한 속성 값. Verifier가 schema에 따라 다시 인코딩
+    pub challenge_binding: F,
+    pub nullifier: F,
+}
+
+impl Policy {
+    /// `AgeCircuit`의 `generate_constraints`가 할당하는 순서의 모든 public input
+    /// 기준 날짜, 조건식의 값, 공개한 속성 값, 발급 정보, revocation root, scope, challenge, challenge binding, nullifier
+    /// 공개한 값이 요청한 속성과 다르거나 schema의 타입에 맞지 않으면 오류
+    pub fn public_inputs<H: CredentialHasher>(
+        &self,
+        issuance: &PublicIssuance<H>,
+        revocation_root: F,
+        scope: F,
+        submitted: &Submitted,
+    ) -> Result<Vec<F>, Error> {
+        let mut inputs = vec![self.today.to_field()];
+        inputs.extend(self.predicate.public_inputs(&self.schema)?);
+        inputs.extend(
+            self.disclosure
+                .public_inputs(&self.schema, &submitted.disclosed)?,
+        );
+        inputs.extend(issuance.public_inputs());
+        inputs.extend([
+            revocation_root,
+            scope,
+            self.challenge,
+            submitted.challenge_binding,
+            submitted.nullifier,
+        ]);
+        Ok(inputs)
+    }
+}
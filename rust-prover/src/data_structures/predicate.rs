@@ -0,0 +1,413 @@
+use std::cmp::Ordering;
+
+use ark_ff::One;
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, prelude::Boolean};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    F,
+    data_structures::{
+        credential::{Credential, CredentialVar},
+        date::{DATE_YEAR_SCALE, Date},
+        schema::{AttributeType, Schema, SchemaError, Value},
+    },
+};
+
+/// predicate에서 사용할 수 있는 credential의 속성
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Attribute {
+    Named(String), // schema에 선언한 속성
+    Age(String), // 기준 날짜(public input)에서의 만 나이. schema의 날짜 속성(생년월일)으로부터 계산
+}
+
+impl Attribute {
+    pub fn named(name: &str) -> Self {
+        Attribute::Named(name.to_string())
+    }
+
+    // 날짜 속성 `date_attribute`를 생년월일로 하는 만 나이
+    pub fn age(date_attribute: &str) -> Self {
+        Attribute::Age(date_attribute.to_string())
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Attribute::Named(name) | Attribute::Age(name) => name,
+        }
+    }
+
+    // 이 속성과 비교하는 값의 타입
+    fn value_type(&self, schema: &Schema) -> Result<AttributeType, PredicateError> {
+        let attribute_type = schema
+            .attribute_type(self.name())
+            .ok_or(PredicateError::UnknownAttribute)?;
+        match self {
+            Attribute::Named(_) => Ok(attribute_type.clone()),
+            Attribute::Age(_) if *attribute_type == AttributeType::Date => {
+                Ok(AttributeType::Integer)
+            }
+            Attribute::Age(_) => Err(PredicateError::TypeMismatch),
+        }
+    }
+}
+
+/// 비교 연산자
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CmpOp {
+    Ge, // >=
+    Le, // <=
+    Eq, // ==
+    Ne, // !=
+}
+
+/// credential 속성에 대한 조건식
+/// 조건식의 모양(구조, 연산자, 속성)이 circuit의 구조를 결정하고, 비교하는 값은 public input이 됨
+/// 따라서 모양이 같은 조건식은 값이 달라도 같은 proving key / verifying key를 사용
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Predicate {
+    Compare {
+        attribute: Attribute,
+        op: CmpOp,
+        value: Value,
+    },
+    // min <= attribute <= max
+    InRange {
+        attribute: Attribute,
+        min: Value,
+        max: Value,
+    },
+    InSet {
+        attribute: Attribute,
+        values: Vec<Value>,
+    },
+    And(Vec<Predicate>), // 비어 있으면 참
+    Or(Vec<Predicate>),  // 비어 있으면 거짓
+}
+
+impl Predicate {
+    pub fn compare(attribute: Attribute, op: CmpOp, value: Value) -> Self {
+        Predicate::Compare {
+            attribute,
+            op,
+            value,
+        }
+    }
+
+    pub fn in_range(attribute: Attribute, min: Value, max: Value) -> Self {
+        Predicate::InRange {
+            attribute,
+            min,
+            max,
+        }
+    }
+
+    pub fn in_set(attribute: Attribute, values: Vec<Value>) -> Self {
+        Predicate::InSet { attribute, values }
+    }
+
+    // 날짜 속성 `date_attribute` 기준 만 `min_age`세 이상
+    pub fn age_at_least(date_attribute: &str, min_age: u32) -> Self {
+        Predicate::compare(
+            Attribute::age(date_attribute),
+            CmpOp::Ge,
+            Value::Integer(min_age as u64),
+        )
+    }
+
+    pub fn and(self, other: Predicate) -> Self {
+        match self {
+            Predicate::And(mut predicates) => {
+                predicates.push(other);
+                Predicate::And(predicates)
+            }
+            predicate => Predicate::And(vec![predicate, other]),
+        }
+    }
+
+    pub fn or(self, other: Predicate) -> Self {
+        match self {
+            Predicate::Or(mut predicates) => {
+                predicates.push(other);
+                Predicate::Or(predicates)
+            }
+            predicate => Predicate::Or(vec![predicate, other]),
+        }
+    }
+
+    // 속성이 schema에 있는지, 속성과 값의 타입이 맞는지, 대소 비교가 없는 타입에 대소 비교를 하지 않는지 확인
+    pub fn check(&self, schema: &Schema) -> Result<(), PredicateError> {
+        let check_value = |attribute: &Attribute, value: &Value, ordered: bool| {
+            let value_type = attribute.value_type(schema)?;
+            value_type.encode(value)?;
+            if ordered && !value_type.is_ordered() {
+                return Err(PredicateError::UnorderedAttribute);
+            }
+            Ok(())
+        };
+
+        match self {
+            Predicate::Compare {
+                attribute,
+                op,
+                value,
+            } => check_value(attribute, value, matches!(op, CmpOp::Ge | CmpOp::Le)),
+            Predicate::InRange {
+                attribute,
+                min,
+                max,
+            } => {
+                check_value(attribute, min, true)?;
+                check_value(attribute, max, true)
+            }
+            Predicate::InSet { attribute, values } => values
+                .iter()
+                .try_for_each(|value| check_value(attribute, value, false)),
+            Predicate::And(predicates) | Predicate::Or(predicates) => predicates
+                .iter()
+                .try_for_each(|predicate| predicate.check(schema)),
+        }
+    }
+
+    /// 조건식의 값들을 circuit에 할당되는 순서대로 나열한 public input
+    /// enum 값은 schema에 선언한 index로 변환
+    pub fn public_inputs(&self, schema: &Schema) -> Result<Vec<F>, PredicateError> {
+        let encode = |attribute: &Attribute, values: &[&Value]| {
+            let value_type = attribute.value_type(schema)?;
+            values
+                .iter()
+                .map(|value| value_type.encode(value).map_err(PredicateError::from))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        match self {
+            Predicate::Compare {
+                attribute, value, ..
+            } => encode(attribute, &[value]),
+            Predicate::InRange {
+                attribute,
+                min,
+                max,
+            } => encode(attribute, &[min, max]),
+            Predicate::InSet { attribute, values } => {
+                encode(attribute, &values.iter().collect::<Vec<_>>())
+            }
+            Predicate::And(predicates) | Predicate::Or(predicates) => Ok(predicates
+                .iter()
+                .map(|predicate| predicate.public_inputs(schema))
+                .collect::<Result<Vec<_>, _>>()?
+                .concat()),
+        }
+    }
+
+    /// `today` 기준으로 credential이 조건식을 만족하는지 circuit 밖에서 계산
+    /// circuit과 같은 방식(YYYYMMDD 정수 비교)으로 계산하므로 결과가 항상 같음
+    pub fn evaluate(&self, credential: &Credential, today: Date) -> Result<bool, PredicateError> {
+        self.check(&credential.schema)?;
+        Ok(self.evaluate_unchecked(credential, today))
+    }
+
+    fn evaluate_unchecked(&self, credential: &Credential, today: Date) -> bool {
+        let schema = &credential.schema;
+        let encode = |attribute: &Attribute, value: &Value| {
+            attribute
+                .value_type(schema)
+                .and_then(|value_type| Ok(value_type.encode(value)?))
+                .expect("checked by Predicate::check")
+        };
+
+        let cmp = |attribute: &Attribute, op: CmpOp, value: &Value| {
+            if let Attribute::Age(name) = attribute {
+                let holder_dob = match credential.attribute(name) {
+                    Some(Value::Date(date)) => date.to_u64(),
+                    _ => unreachable!("checked by Predicate::check"),
+                };
+                let age = match value {
+                    Value::Integer(n) => *n,
+                    _ => unreachable!("checked by Predicate::check"),
+                };
+                // 기준 날짜에 만 n세 이상: n번째 생일이 기준 날짜와 같거나 이전
+                let age_at_least = |n: u64| holder_dob + n * DATE_YEAR_SCALE <= today.to_u64();
+                let at_least = age_at_least(age);
+                let at_most = !age_at_least(age + 1);
+                return match op {
+                    CmpOp::Ge => at_least,
+                    CmpOp::Le => at_most,
+                    CmpOp::Eq => at_least && at_most,
+                    CmpOp::Ne => !(at_least && at_most),
+                };
+            }
+            // 대소 비교는 Integer / Date 속성에만 사용하므로 field 원소를 정수로 비교해도 됨
+            let attribute_value = credential
+                .attribute_field(attribute.name())
+                .expect("checked by Predicate::check");
+            let value = encode(attribute, value);
+            match op {
+                CmpOp::Ge => attribute_value >= value,
+                CmpOp::Le => attribute_value <= value,
+                CmpOp::Eq => attribute_value == value,
+                CmpOp::Ne => attribute_value != value,
+            }
+        };
+
+        match self {
+            Predicate::Compare {
+                attribute,
+                op,
+                value,
+            } => cmp(attribute, *op, value),
+            Predicate::InRange {
+                attribute,
+                min,
+                max,
+            } => cmp(attribute, CmpOp::Ge, min) && cmp(attribute, CmpOp::Le, max),
+            Predicate::InSet { attribute, values } => {
+                values.iter().any(|value| cmp(attribute, CmpOp::Eq, value))
+            }
+            Predicate::And(predicates) => predicates
+                .iter()
+                .all(|p| p.evaluate_unchecked(credential, today)),
+            Predicate::Or(predicates) => predicates
+                .iter()
+                .any(|p| p.evaluate_unchecked(credential, today)),
+        }
+    }
+
+    /// 조건식을 constraint로 변환. 비교하는 값을 public input으로 할당하고 조건식의 참 / 거짓을 반환
+    /// public input은 `public_inputs`와 같은 순서로 할당
+    pub fn generate_constraints(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        credential: &CredentialVar,
+        today: &FpVar<F>,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        self.check(&credential.schema)
+            .map_err(|_| SynthesisError::Unsatisfiable)?;
+
+        let value_var = |attribute: &Attribute, value: &Value| {
+            let value = attribute
+                .value_type(&credential.schema)
+                .and_then(|value_type| Ok(value_type.encode(value)?))
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            FpVar::new_input(cs.clone(), || Ok(value))
+        };
+        let cmp_var = |attribute: &Attribute, op: CmpOp, value: &FpVar<F>| {
+            compare_var(credential, today, attribute, op, value)
+        };
+
+        match self {
+            Predicate::Compare {
+                attribute,
+                op,
+                value,
+            } => cmp_var(attribute, *op, &value_var(attribute, value)?),
+            Predicate::InRange {
+                attribute,
+                min,
+                max,
+            } => {
+                let min_var = value_var(attribute, min)?;
+                let max_var = value_var(attribute, max)?;
+                cmp_var(attribute, CmpOp::Ge, &min_var)?.and(&cmp_var(
+                    attribute,
+                    CmpOp::Le,
+                    &max_var,
+                )?)
+            }
+            Predicate::InSet { attribute, values } => {
+                let mut is_member = Boolean::FALSE;
+                for value in values {
+                    is_member = is_member.or(&cmp_var(
+                        attribute,
+                        CmpOp::Eq,
+                        &value_var(attribute, value)?,
+                    )?)?;
+                }
+                Ok(is_member)
+            }
+            Predicate::And(predicates) => {
+                let mut result = Boolean::TRUE;
+                for predicate in predicates {
+                    result = result.and(&predicate.generate_constraints(
+                        cs.clone(),
+                        credential,
+                        today,
+                    )?)?;
+                }
+                Ok(result)
+            }
+            Predicate::Or(predicates) => {
+                let mut result = Boolean::FALSE;
+                for predicate in predicates {
+                    result = result.or(&predicate.generate_constraints(
+                        cs.clone(),
+                        credential,
+                        today,
+                    )?)?;
+                }
+                Ok(result)
+            }
+        }
+    }
+}
+
+// circuit 안에서 `attribute op value`
+fn compare_var(
+    credential: &CredentialVar,
+    today: &FpVar<F>,
+    attribute: &Attribute,
+    op: CmpOp,
+    value: &FpVar<F>,
+) -> Result<Boolean<F>, SynthesisError> {
+    let attribute_var = credential
+        .attribute(attribute.name())
+        .ok_or(SynthesisError::Unsatisfiable)?;
+
+    // 만 나이는 생년월일로 비교. 만 n세 이상 <=> 생년월일 + n * 10000 <= 기준 날짜
+    if let Attribute::Age(_) = attribute {
+        let age_at_least = |n: &FpVar<F>| {
+            (attribute_var + n * F::from(DATE_YEAR_SCALE)).is_cmp(today, Ordering::Less, true)
+        };
+        let at_least = || age_at_least(value);
+        let at_most = || Ok::<_, SynthesisError>(age_at_least(&(value + F::one()))?.not());
+        return match op {
+            CmpOp::Ge => at_least(),
+            CmpOp::Le => at_most(),
+            CmpOp::Eq => at_least()?.and(&at_most()?),
+            CmpOp::Ne => Ok(at_least()?.and(&at_most()?)?.not()),
+        };
+    }
+
+    match op {
+        CmpOp::Ge => attribute_var.is_cmp(value, Ordering::Greater, true),
+        CmpOp::Le => attribute_var.is_cmp(value, Ordering::Less, true),
+        CmpOp::Eq => attribute_var.is_eq(value),
+        CmpOp::Ne => attribute_var.is_neq(value),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PredicateError {
+    #[error("predicate refers to an attribute not declared in the schema")]
+    UnknownAttribute,
+    // 만 나이는 날짜 속성에만 사용 가능
+    #[error("predicate value does not match the attribute type")]
+    TypeMismatch,
+    #[error("predicate value is not a declared enum variant")]
+    UnknownVariant,
+    #[error("string and enum attributes cannot be compared with >= or <=")]
+    UnorderedAttribute,
+    #[error("predicate string value is longer than the encoding limit")]
+    StringTooLong,
+}
+
+impl From<SchemaError> for PredicateError {
+    fn from(error: SchemaError) -> Self {
+        match error {
+            SchemaError::UnknownVariant => PredicateError::UnknownVariant,
+            SchemaError::UnknownAttribute => PredicateError::UnknownAttribute,
+            SchemaError::StringTooLong => PredicateError::StringTooLong,
+            _ => PredicateError::TypeMismatch,
+        }
+    }
+}
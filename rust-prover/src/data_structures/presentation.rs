@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    F, Groth16Proof,
+    data_structures::{
+        circuit::IssuanceMode,
+        credential::CredentialEncoding,
+        date::Date,
+        disclosure::{DisclosedValues, Disclosure},
+        issuer_set::IssuerSet,
+        nullifier::scope_from_id,
+        policy::Policy,
+        predicate::Predicate,
+        schema::Schema,
+    },
+    utils::serialization::canonical,
+};
+
+/// Verifier가 Holder에게 보내는 증명 요청
+/// credential은 `issuers` 중 한 Issuer의 서명으로 증명 (`IssuanceMode::TrustedIssuers`)
+/// circuit의 구조를 결정하는 값(tree 높이, schema, 조건식의 모양, 공개할 속성, 할당 방식)은 Verifier의 setup과 같아야 함
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PresentationRequest {
+    pub verifier_id: [u8; 32], // 증명의 scope를 결정
+    #[serde(with = "canonical")]
+    pub challenge: F, // 요청마다 새로 발급한 challenge
+    pub today: Date,           // 조건식과 유효 기간의 기준 날짜
+    pub tree_height: usize,    // credential / revocation tree의 높이
+    pub schema: Schema,
+    pub predicate: Predicate,
+    pub disclosure: Disclosure,
+    pub encoding: CredentialEncoding,
+    pub issuers: IssuerSet,  // 신뢰하는 Issuer 목록
+    pub reveal_issuer: bool, // credential의 issuer_id 공개 여부
+}
+
+impl PresentationRequest {
+    pub fn scope(&self) -> F {
+        scope_from_id(&self.verifier_id)
+    }
+
+    // public input 중 요청으로 정해지는 값
+    pub fn policy(&self) -> Policy {
+        Policy {
+            today: self.today,
+            schema: self.schema.clone(),
+            predicate: self.predicate.clone(),
+            disclosure: self.disclosure.clone(),
+            challenge: self.challenge,
+        }
+    }
+
+    // setup에 사용하는 발급 확인 방식
+    pub fn mode(&self) -> IssuanceMode {
+        IssuanceMode::TrustedIssuers {
+            reveal_issuer: self.reveal_issuer,
+        }
+    }
+}
+
+/// Holder가 증명 요청에 답하여 Verifier에게 제출하는 증명
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Presentation {
+    pub verifier_id: [u8; 32], // 요청한 Verifier
+    #[serde(with = "canonical")]
+    pub challenge: F, // 요청의 challenge
+    #[serde(with = "canonical")]
+    pub proof: Groth16Proof,
+    #[serde(with = "canonical")]
+    pub public_inputs: Vec<F>, // `AgeCircuit::public_inputs`와 같은 순서의 모든 public input
+    pub disclosed: DisclosedValues, // 공개한 속성 값. public input에는 인코딩된 값이 들어 있음
+    pub revealed_issuer: Option<[u8; 32]>, // 요청이 issuer_id 공개를 요구하면 credential의 issuer_id
+}
+
+impl Presentation {
+    // Holder와 Verifier의 scope로 결정되는 nullifier (마지막 public input)
+    pub fn nullifier(&self) -> Option<F> {
+        self.public_inputs.last().copied()
+    }
+
+    // credential과 challenge로 결정되는 challenge binding (nullifier 앞의 public input)
+    pub fn challenge_binding(&self) -> Option<F> {
+        self.public_inputs.iter().rev().nth(1).copied()
+    }
+}
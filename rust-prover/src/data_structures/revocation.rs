@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use ark_crypto_primitives::merkle_tree::{MerkleTree, Path, constraints::PathVar};
+use ark_ff::{One, Zero};
+
+use crate::{
+    F,
+    data_structures::{
+        hasher::{CredentialHasher, PoseidonHasher},
+        merkle_tree::{
+            PoseidonCredentialTreeConfig, PoseidonCredentialTreeConfigVar, tree_capacity,
+        },
+    },
+    error::Error,
+};
+
+/// Issuer가 폐기한 credential의 sparse Merkle tree
+/// - key: credential 번호 (credential tree에서의 leaf index). 높이는 credential tree와 같음
+/// - leaf: 폐기되었으면 `revoked_leaf()`, 아니면 `not_revoked_leaf()`. 빈 자리도 `not_revoked_leaf()`
+/// - 해시: credential의 해시 방식과 관계없이 Poseidon. root는 field 원소 1개의 public input
+///
+/// Holder는 자신의 credential 번호의 leaf가 `not_revoked_leaf()`임을 증명 (non-membership)
+pub type RevocationTree = MerkleTree<PoseidonCredentialTreeConfig>;
+/// credential 번호의 leaf에서 revocation root까지의 Merkle path
+pub type RevocationPath = Path<PoseidonCredentialTreeConfig>;
+pub type RevocationPathVar =
+    PathVar<PoseidonCredentialTreeConfig, F, PoseidonCredentialTreeConfigVar>;
+
+/// Issuer마다 공개한 폐기 credential 번호 리스트 (issuer_id → 번호들)
+/// Holder가 여러 Issuer의 credential 중 폐기되지 않은 것을 고를 때 사용
+pub type RevocationLists = HashMap<[u8; 32], Vec<usize>>;
+
+// 폐기되지 않은 credential 번호의 leaf
+pub fn not_revoked_leaf() -> [F; 1] {
+    [F::zero()]
+}
+
+// 폐기된 credential 번호의 leaf
+pub fn revoked_leaf() -> [F; 1] {
+    [F::one()]
+}
+
+/// 폐기된 credential 번호 리스트로 revocation tree 생성
+pub fn build_revocation_tree(
+    tree_height: usize,
+    revoked: &[usize],
+) -> Result<RevocationTree, Error> {
+    let capacity = tree_capacity(tree_height);
+    let mut leaves = vec![not_revoked_leaf(); capacity];
+    for &index in revoked {
+        *leaves.get_mut(index).ok_or(Error::CredentialNotIssued)? = revoked_leaf();
+    }
+
+    RevocationTree::new(
+        PoseidonHasher::leaf_params(),
+        PoseidonHasher::two_to_one_params(),
+        leaves,
+    )
+    .map_err(Error::credential_tree)
+}
+
+/// Issuer가 publish하는 revocation tree의 root
+pub fn revocation_root(tree_height: usize, revoked: &[usize]) -> Result<F, Error> {
+    Ok(build_revocation_tree(tree_height, revoked)?.root())
+}
+
+/// `index`번째 credential이 폐기되지 않았음을 보이는 Merkle path
+pub fn non_revocation_path(
+    tree_height: usize,
+    revoked: &[usize],
+    index: usize,
+) -> Result<RevocationPath, Error> {
+    if revoked.contains(&index) {
+        return Err(Error::CredentialRevoked);
+    }
+    build_revocation_tree(tree_height, revoked)?
+        .generate_proof(index)
+        .map_err(Error::credential_tree)
+}
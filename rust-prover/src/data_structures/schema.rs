@@ -0,0 +1,172 @@
+use ark_crypto_primitives::crh::sha256::{Sha256, digest::Digest};
+use ark_ff::PrimeField;
+use serde::{Deserialize, Serialize};
+
+use crate::{F, data_structures::date::Date, utils::utils::string_to_field};
+
+/// schema 하나에 선언할 수 있는 속성의 최대 개수
+/// credential 직렬화(issuer_id, schema id, holder 공개 키, 발급일, 만료일, 속성들, randomness)가 Pedersen leaf 해시의 입력(32바이트 x 12)에 들어가야 함
+pub const MAX_SCHEMA_ATTRIBUTES: usize = 6;
+
+/// 속성의 타입. 모든 속성은 circuit 안에서 field 원소 하나로 할당
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AttributeType {
+    Integer,           // u64
+    Date,              // YYYYMMDD
+    String,            // UTF-8로 MAX_STRING_BYTES(31)바이트 이하의 문자열
+    Enum(Vec<String>), // 선언한 값 중 하나. 값의 index로 할당
+}
+
+impl AttributeType {
+    // 값이 이 타입에 맞는지 확인하고 field 원소로 변환
+    pub fn encode(&self, value: &Value) -> Result<F, SchemaError> {
+        match (self, value) {
+            (AttributeType::Integer, Value::Integer(n)) => Ok(F::from(*n)),
+            (AttributeType::Date, Value::Date(date)) => Ok(date.to_field()),
+            (AttributeType::String, Value::String(s)) => {
+                string_to_field(s).map_err(|_| SchemaError::StringTooLong)
+            }
+            (AttributeType::Enum(variants), Value::Enum(variant)) => variants
+                .iter()
+                .position(|v| v == variant)
+                .map(|index| F::from(index as u64))
+                .ok_or(SchemaError::UnknownVariant),
+            _ => Err(SchemaError::TypeMismatch),
+        }
+    }
+
+    // 값의 대소 비교가 의미 있는 타입
+    pub fn is_ordered(&self) -> bool {
+        matches!(self, AttributeType::Integer | AttributeType::Date)
+    }
+
+    // setup용 mock credential에 넣는 값
+    fn default_value(&self) -> Value {
+        match self {
+            AttributeType::Integer => Value::Integer(0),
+            AttributeType::Date => Value::Date(Date::UNIX_EPOCH),
+            AttributeType::String => Value::String(String::new()),
+            AttributeType::Enum(variants) => Value::Enum(variants[0].clone()),
+        }
+    }
+}
+
+/// 속성의 값. credential의 속성과 predicate에서 비교하는 값에 사용
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Value {
+    Integer(u64),
+    Date(Date),
+    String(String),
+    Enum(String),
+}
+
+/// Issuer가 발급하는 credential의 속성 목록
+/// 선언한 순서대로 credential을 직렬화하고 circuit에 할당
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schema {
+    attributes: Vec<(String, AttributeType)>,
+}
+
+impl Schema {
+    pub fn new(attributes: Vec<(&str, AttributeType)>) -> Result<Self, SchemaError> {
+        if attributes.len() > MAX_SCHEMA_ATTRIBUTES {
+            return Err(SchemaError::TooManyAttributes);
+        }
+        for (i, (name, attribute_type)) in attributes.iter().enumerate() {
+            if attributes[..i].iter().any(|(other, _)| other == name) {
+                return Err(SchemaError::DuplicateAttribute);
+            }
+            if let AttributeType::Enum(variants) = attribute_type
+                && variants.is_empty()
+            {
+                return Err(SchemaError::EmptyEnum);
+            }
+        }
+
+        Ok(Schema {
+            attributes: attributes
+                .into_iter()
+                .map(|(name, attribute_type)| (name.to_string(), attribute_type))
+                .collect(),
+        })
+    }
+
+    pub fn attributes(&self) -> &[(String, AttributeType)] {
+        &self.attributes
+    }
+
+    // credential 해시에 포함하는 schema 식별자. 속성 이름, 타입, enum 값과 순서를 JSON으로 직렬화한 SHA256
+    // 타입 구성이 같은 다른 schema의 credential로 바꿔서 증명할 수 없음
+    pub fn id(&self) -> F {
+        let json = serde_json::to_vec(self).expect("schema serializes to JSON");
+        F::from_le_bytes_mod_order(&Sha256::digest(json))
+    }
+
+    // 속성이 선언된 순서
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.attributes.iter().position(|(n, _)| n == name)
+    }
+
+    pub fn attribute_type(&self, name: &str) -> Option<&AttributeType> {
+        self.index_of(name).map(|i| &self.attributes[i].1)
+    }
+
+    // 이름과 값 목록을 schema 순서로 정렬. 모든 속성이 정확히 한 번씩 타입에 맞게 있어야 함
+    pub fn order_values(&self, values: Vec<(&str, Value)>) -> Result<Vec<Value>, SchemaError> {
+        let mut ordered: Vec<Option<Value>> = vec![None; self.attributes.len()];
+        for (name, value) in values {
+            let index = self.index_of(name).ok_or(SchemaError::UnknownAttribute)?;
+            if ordered[index].is_some() {
+                return Err(SchemaError::DuplicateAttribute);
+            }
+            self.attributes[index].1.encode(&value)?;
+            ordered[index] = Some(value);
+        }
+        ordered
+            .into_iter()
+            .map(|value| value.ok_or(SchemaError::MissingAttribute))
+            .collect()
+    }
+
+    // schema 순서의 값 목록이 모든 속성에 하나씩 타입에 맞게 있는지 확인
+    pub fn check_values(&self, values: &[Value]) -> Result<(), SchemaError> {
+        if values.len() < self.attributes.len() {
+            return Err(SchemaError::MissingAttribute);
+        }
+        if values.len() > self.attributes.len() {
+            return Err(SchemaError::UnknownAttribute);
+        }
+        for ((_, attribute_type), value) in self.attributes.iter().zip(values) {
+            attribute_type.encode(value)?;
+        }
+        Ok(())
+    }
+
+    // setup용 mock credential의 속성 값
+    pub fn default_values(&self) -> Vec<Value> {
+        self.attributes
+            .iter()
+            .map(|(_, attribute_type)| attribute_type.default_value())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SchemaError {
+    #[error("schema declares more than {MAX_SCHEMA_ATTRIBUTES} attributes")]
+    TooManyAttributes,
+    #[error("attribute is given more than once")]
+    DuplicateAttribute,
+    #[error("enum attribute declares no variants")]
+    EmptyEnum,
+    #[error("attribute is not declared in the schema")]
+    UnknownAttribute,
+    #[error("attribute declared in the schema has no value")]
+    MissingAttribute,
+    #[error("value does not match the attribute type")]
+    TypeMismatch,
+    #[error("value is not a declared enum variant")]
+    UnknownVariant,
+    #[error("string value is longer than the encoding limit")]
+    StringTooLong,
+}
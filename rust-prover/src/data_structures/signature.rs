@@ -0,0 +1,199 @@
+use std::borrow::Borrow;
+
+use ark_crypto_primitives::crh::{
+    CRHScheme, CRHSchemeGadget,
+    poseidon::{CRH as PoseidonCRH, constraints::CRHGadget as PoseidonCRHGadget},
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ed_on_bn254::{EdwardsAffine, Fr as EdFr, constraints::EdwardsVar};
+use ark_ff::{BigInteger, One, PrimeField, UniformRand};
+use ark_r1cs_std::{
+    ToBitsGadget,
+    alloc::{AllocVar, AllocationMode},
+    eq::EqGadget,
+    fields::fp::FpVar,
+    groups::CurveVar,
+    prelude::Boolean,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    F,
+    data_structures::{
+        credential::{Credential, CredentialVar},
+        hasher::{CredentialHasher, PoseidonHasher},
+    },
+    utils::{serialization::canonical, utils::field_to_bytes},
+};
+
+// 서명하는 메시지 / nonce 해시의 domain tag. Holder 공개 키, nullifier 해시와 값이 겹치지 않게 함
+const MESSAGE_DOMAIN: u64 = 3;
+const NONCE_DOMAIN: u64 = 4;
+
+/// Issuer의 EdDSA 서명 키 (BabyJubjub 위의 Poseidon EdDSA)
+/// - 공개 키: A = sk * B (B는 BabyJubjub의 generator)
+/// - 서명: R = r * B, S = r + c * sk. c = Poseidon(R, A, m)
+/// - 검증: S * B == R + c * A, S < l (l은 prime order 부분군의 크기)
+///
+/// Issuer는 credential마다 서명만 전달하면 되고 publish할 것은 공개 키 하나뿐
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IssuerSigningKey {
+    secret_key: EdFr,
+    pub public_key: EdwardsAffine,
+}
+
+/// Issuer가 credential과 credential 번호에 한 서명
+/// 번호는 Issuer가 발급한 순서이며 revocation tree의 key로 사용
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialSignature {
+    pub serial: u64,
+    #[serde(with = "canonical")]
+    pub r: EdwardsAffine,
+    #[serde(with = "canonical")]
+    pub s: EdFr, // l보다 작은 값. l 이상의 값은 역직렬화에서 거부
+}
+
+impl IssuerSigningKey {
+    pub fn new(secret_key: EdFr) -> Self {
+        IssuerSigningKey {
+            secret_key,
+            public_key: (EdwardsAffine::generator() * secret_key).into_affine(),
+        }
+    }
+
+    pub fn generate<R: Rng>(rng: &mut R) -> Self {
+        Self::new(EdFr::rand(rng))
+    }
+
+    // nonce r는 비밀 키와 메시지로 결정적으로 계산 (같은 r을 다른 메시지에 쓰면 비밀 키가 드러남)
+    pub fn sign(&self, credential: &Credential, serial: u64) -> CredentialSignature {
+        let message = signed_message(credential, serial);
+        let nonce = poseidon(&[
+            F::from(NONCE_DOMAIN),
+            F::from_le_bytes_mod_order(&self.secret_key.into_bigint().to_bytes_le()),
+            message,
+        ]);
+        let r = EdFr::from_le_bytes_mod_order(&field_to_bytes(nonce));
+        let r_point = (EdwardsAffine::generator() * r).into_affine();
+        let c = to_scalar(challenge(&r_point, &self.public_key, message));
+
+        CredentialSignature {
+            serial,
+            r: r_point,
+            s: r + c * self.secret_key,
+        }
+    }
+}
+
+/// Issuer가 서명하는 메시지: credential의 Poseidon 해시와 credential 번호의 해시
+pub fn signed_message(credential: &Credential, serial: u64) -> F {
+    poseidon(&[
+        F::from(MESSAGE_DOMAIN),
+        PoseidonHasher::hash(credential),
+        F::from(serial),
+    ])
+}
+
+/// `public_key`로 credential의 서명 검증
+/// S는 `EdFr` 값이므로 항상 l보다 작음 (S + l처럼 같은 식을 만족하는 다른 서명은 표현할 수 없음)
+pub fn verify_signature(
+    public_key: &EdwardsAffine,
+    credential: &Credential,
+    signature: &CredentialSignature,
+) -> bool {
+    let message = signed_message(credential, signature.serial);
+    let c = to_scalar(challenge(&signature.r, public_key, message));
+    EdwardsAffine::generator() * signature.s == signature.r + *public_key * c
+}
+
+fn challenge(r: &EdwardsAffine, public_key: &EdwardsAffine, message: F) -> F {
+    poseidon(&[r.x, r.y, public_key.x, public_key.y, message])
+}
+
+// challenge를 곡선의 scalar로 변환. circuit은 challenge의 비트로 곱하므로 prime order 점에 대해 같은 결과
+fn to_scalar(challenge: F) -> EdFr {
+    EdFr::from_le_bytes_mod_order(&field_to_bytes(challenge))
+}
+
+fn poseidon(input: &[F]) -> F {
+    PoseidonCRH::<F>::evaluate(PoseidonHasher::leaf_params(), input)
+        .expect("poseidon accepts any number of field elements")
+}
+
+/// circuit 안에서 사용하는 `CredentialSignature`
+/// 번호와 S는 비트로, R은 곡선 위의 점으로 할당
+pub struct CredentialSignatureVar {
+    pub serial: Vec<Boolean<F>>, // Little-endian 64비트
+    pub r: EdwardsVar,
+    pub s: Vec<Boolean<F>>, // Little-endian `EdFr::MODULUS_BIT_SIZE`비트. `verify`에서 l보다 작은지 확인
+}
+
+impl AllocVar<CredentialSignature, F> for CredentialSignatureVar {
+    fn new_variable<T: Borrow<CredentialSignature>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let cs = cs.into().cs();
+        let signature = f()?.borrow().clone();
+
+        let serial_bits: Vec<bool> = (0..64).map(|i| (signature.serial >> i) & 1 == 1).collect();
+        let mut s_bits = signature.s.into_bigint().to_bits_le();
+        s_bits.truncate(EdFr::MODULUS_BIT_SIZE as usize);
+
+        Ok(CredentialSignatureVar {
+            serial: Vec::new_variable(cs.clone(), || Ok(serial_bits), mode)?,
+            r: EdwardsVar::new_variable(cs.clone(), || Ok(signature.r), mode)?,
+            s: Vec::new_variable(cs.clone(), || Ok(s_bits), mode)?,
+        })
+    }
+}
+
+impl CredentialSignatureVar {
+    // credential 번호를 field 원소로 변환
+    pub fn serial_field(&self) -> Result<FpVar<F>, SynthesisError> {
+        Boolean::le_bits_to_fp_var(&self.serial)
+    }
+
+    // `signed_message`, `verify_signature`와 동일하게 circuit 안에서 서명 검증
+    // S >= l이면 (S + l도 같은 식을 만족하므로) 반환값과 상관없이 constraint가 만족되지 않음
+    pub fn verify(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        public_key: &EdwardsVar,
+        credential: &CredentialVar,
+    ) -> Result<Boolean<F>, SynthesisError> {
+        let params_var = PoseidonHasher::leaf_params_var(cs)?;
+        let credential_hash_var = PoseidonHasher::hash_var(&params_var, credential)?;
+        let message_var = PoseidonCRHGadget::<F>::evaluate(
+            &params_var,
+            &[
+                FpVar::Constant(F::from(MESSAGE_DOMAIN)),
+                credential_hash_var,
+                self.serial_field()?,
+            ],
+        )?;
+        let challenge_var = PoseidonCRHGadget::<F>::evaluate(
+            &params_var,
+            &[
+                self.r.x.clone(),
+                self.r.y.clone(),
+                public_key.x.clone(),
+                public_key.y.clone(),
+                message_var,
+            ],
+        )?;
+
+        // S <= l - 1
+        // 반환값은 l - 1의 하위 비트 중 연속된 1에 대응하는 S의 비트로, 어떤 값이어도 S <= l - 1이므로 사용하지 않음
+        let _ = Boolean::enforce_smaller_or_equal_than_le(&self.s, (-EdFr::one()).into_bigint())?;
+
+        // S * B == R + c * A
+        let lhs = EdwardsVar::constant(EdwardsAffine::generator().into_group())
+            .scalar_mul_le(self.s.iter())?;
+        let rhs = self.r.clone() + public_key.scalar_mul_le(challenge_var.to_bits_le()?.iter())?;
+        lhs.is_eq(&rhs)
+    }
+}
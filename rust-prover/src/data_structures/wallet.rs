@@ -0,0 +1,174 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    F,
+    data_structures::{
+        circuit::{AgeCircuit, Issuance},
+        credential::Credential,
+        hasher::CredentialHasher,
+        holder_key::HolderKey,
+        presentation::PresentationRequest,
+        revocation::{RevocationLists, non_revocation_path, revocation_root},
+        signature::{CredentialSignature, verify_signature},
+    },
+    error::Error,
+};
+
+/// Issuer에게서 받은 credential과 Issuer 서명
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredCredential {
+    pub credential: Credential,
+    pub signature: CredentialSignature,
+}
+
+/// Holder 키와 여러 Issuer에게서 받은 credential을 보관하는 wallet. JSON 파일로 저장
+/// 파일에 Holder 비밀 키가 들어 있으므로 Holder만 읽을 수 있는 곳에 저장해야 함
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Wallet {
+    key: HolderKey,                     // 모든 credential에 들어 있는 공개 키의 키 쌍
+    credentials: Vec<StoredCredential>, // 받은 순서
+}
+
+impl Wallet {
+    pub fn new(key: HolderKey) -> Self {
+        Wallet {
+            key,
+            credentials: vec![],
+        }
+    }
+
+    pub fn public_key(&self) -> F {
+        self.key.public_key
+    }
+
+    pub fn key(&self) -> &HolderKey {
+        &self.key
+    }
+
+    pub fn credentials(&self) -> &[StoredCredential] {
+        &self.credentials
+    }
+
+    // 이 wallet의 공개 키로 발급받은 credential만 보관
+    pub fn insert(
+        &mut self,
+        credential: Credential,
+        signature: CredentialSignature,
+    ) -> Result<(), Error> {
+        if credential.holder_public_key != self.key.public_key {
+            return Err(Error::HolderKeyMismatch);
+        }
+        self.credentials.push(StoredCredential {
+            credential,
+            signature,
+        });
+        Ok(())
+    }
+
+    // Holder 비밀 키가 평문으로 들어가므로 unix에서는 소유자만 읽고 쓸 수 있는 파일(0600)로 저장
+    // 이미 있는 파일은 권한을 0600으로 바꾼 뒤 덮어씀
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            if path.as_ref().exists() {
+                fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        options
+            .open(path)?
+            .write_all(&serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// 요청을 만족하는 첫 credential
+    /// schema id가 같고, 신뢰하는 Issuer의 서명이 있고, 폐기되지 않았고, 기준 날짜에 유효하고, 조건식을 만족해야 함
+    /// `revocations`에 폐기 리스트가 없거나, 폐기 리스트의 root가 issuer set에 등록된 root와 다른 Issuer의 credential은 사용하지 않음
+    pub fn find(
+        &self,
+        request: &PresentationRequest,
+        revocations: &RevocationLists,
+    ) -> Option<&StoredCredential> {
+        self.credentials.iter().find(|stored| {
+            let credential = &stored.credential;
+            let is_trusted = request
+                .issuers
+                .membership_path(&credential.issuer_id)
+                .is_ok_and(|(public_key, _)| {
+                    verify_signature(&public_key, credential, &stored.signature)
+                });
+            let is_revoked = revocations
+                .get(&credential.issuer_id)
+                .is_none_or(|revoked| revoked.contains(&(stored.signature.serial as usize)));
+            let is_current = revocations
+                .get(&credential.issuer_id)
+                .zip(request.issuers.issuer(&credential.issuer_id))
+                .is_some_and(|(revoked, issuer)| {
+                    revocation_root(request.tree_height, revoked)
+                        .is_ok_and(|root| root == issuer.revocation_root)
+                });
+
+            credential.schema.id() == request.schema.id()
+                && is_trusted
+                && !is_revoked
+                && is_current
+                && credential.validity.contains(request.today)
+                && matches!(
+                    request.predicate.evaluate(credential, request.today),
+                    Ok(true)
+                )
+        })
+    }
+
+    /// 요청을 만족하는 credential로 증명할 circuit. 만족하는 credential이 없으면 `NoMatchingCredential`
+    pub fn circuit<H: CredentialHasher>(
+        &self,
+        request: &PresentationRequest,
+        revocations: &RevocationLists,
+    ) -> Result<AgeCircuit<H>, Error> {
+        let stored = self
+            .find(request, revocations)
+            .ok_or(Error::NoMatchingCredential)?;
+        let credential = &stored.credential;
+        let revoked = &revocations[&credential.issuer_id];
+        let (issuer_public_key, issuer_path) =
+            request.issuers.membership_path(&credential.issuer_id)?;
+
+        Ok(AgeCircuit {
+            today: request.today,
+            predicate: request.predicate.clone(),
+            disclosure: request.disclosure.clone(),
+            encoding: request.encoding,
+            issuance: Issuance::TrustedIssuers {
+                issuer_set_root: request.issuers.root()?,
+                revealed_issuer: request.reveal_issuer.then_some(credential.issuer_id),
+                issuer_public_key,
+                issuer_path,
+                signature: stored.signature.clone(),
+            },
+            scope: request.scope(),
+            challenge: request.challenge,
+            holder_secret_key: self.key.secret_key(),
+            credential: credential.clone(),
+            revocation_root: revocation_root(request.tree_height, revoked)?,
+            non_revocation_path: non_revocation_path(
+                request.tree_height,
+                revoked,
+                stored.signature.serial as usize,
+            )?,
+        })
+    }
+}
@@ -1,7 +1,22 @@
 use crate::{
-    Groth16Proof, Groth16ProvingKey,
-    data_structures::{circuit::AgeCircuit, credential::Credential},
-    utils::utils::string_to_bytes,
+    F, Groth16Proof, Groth16ProvingKey,
+    data_structures::{
+        challenge::challenge_binding,
+        circuit::{AgeCircuit, CredentialClaim},
+        credential::Credential,
+        date::Date,
+        diagnostics::{SectionedCircuit, Unsatisfied, which_is_unsatisfied},
+        disclosure::{DisclosedValues, Disclosure},
+        hasher::CredentialHasher,
+        holder_key::HolderKey,
+        merkle_tree::{CredentialPath, membership_path},
+        multi_credential::MultiCredentialCircuit,
+        nullifier::nullifier,
+        presentation::{Presentation, PresentationRequest},
+        revocation::{RevocationLists, RevocationPath, non_revocation_path},
+        wallet::Wallet,
+    },
+    error::Error,
 };
 
 use ark_bn254::Bn254;
@@ -9,28 +24,170 @@ use ark_crypto_primitives::snark::SNARK;
 use ark_groth16::Groth16;
 
 pub struct Holder {
-    pub id: [u8; 32],
-    pub credentials: Credential,
+    key: HolderKey, // credential에 들어 있는 공개 키의 키 쌍. 비밀 키는 Holder만 알고 있음
+    pub credential: Credential, // 증명에 사용하는 credential. 여러 credential은 `Wallet`에 보관
 }
 
 impl Holder {
-    pub fn new(id: &str, cred: Credential) -> Self {
-        Holder {
-            id: string_to_bytes(id),
-            credentials: cred,
+    // credential은 `key`의 공개 키로 발급받은 것이어야 함
+    pub fn new(key: HolderKey, cred: Credential) -> Result<Self, Error> {
+        if cred.holder_public_key != key.public_key {
+            return Err(Error::HolderKeyMismatch);
         }
+        Ok(Holder {
+            key,
+            credential: cred,
+        })
     }
 
-    pub fn prove(
+    // Issuer가 publish한 해시 리스트로 credential tree를 만들어 자신의 credential에 대한 Merkle path 계산
+    pub fn membership_path<H: CredentialHasher>(
+        &self,
+        tree_height: usize,
+        hashed_credentials: &[H::Digest],
+    ) -> Result<CredentialPath<H>, Error> {
+        let hashed_credential = H::hash(&self.credential);
+        let index = hashed_credentials
+            .iter()
+            .position(|h| *h == hashed_credential)
+            .ok_or(Error::CredentialNotPublished)?;
+
+        membership_path::<H>(tree_height, hashed_credentials, index).map_err(Error::credential_tree)
+    }
+
+    // Issuer가 publish한 폐기 리스트로 revocation tree를 만들어 자신의 credential이 폐기되지 않았음을 보이는 Merkle path 계산
+    // credential 번호는 credential tree에서의 index
+    pub fn non_revocation_path<H: CredentialHasher>(
+        &self,
+        tree_height: usize,
+        hashed_credentials: &[H::Digest],
+        revoked: &[usize],
+    ) -> Result<RevocationPath, Error> {
+        let hashed_credential = H::hash(&self.credential);
+        let index = hashed_credentials
+            .iter()
+            .position(|h| *h == hashed_credential)
+            .ok_or(Error::CredentialNotPublished)?;
+
+        non_revocation_path(tree_height, revoked, index)
+    }
+
+    pub fn public_key(&self) -> F {
+        self.key.public_key
+    }
+
+    // circuit의 witness로 사용
+    pub fn secret_key(&self) -> F {
+        self.key.secret_key()
+    }
+
+    // `scope`의 Verifier에게 제출하는 nullifier. 같은 scope에는 항상 같은 값
+    pub fn nullifier(&self, scope: F) -> F {
+        nullifier(self.key.secret_key(), scope)
+    }
+
+    // Verifier가 발급한 `challenge`에 대한 challenge binding. 증명 요청마다 달라짐
+    pub fn challenge_binding(&self, challenge: F) -> F {
+        challenge_binding(self.key.secret_key(), &self.credential, challenge)
+    }
+
+    // Verifier가 요청한 속성의 값. 증명과 함께 제출
+    pub fn disclose(&self, disclosure: &Disclosure) -> Result<DisclosedValues, Error> {
+        Ok(disclosure.reveal(&self.credential)?)
+    }
+
+    // 여러 credential(다른 Issuer의 credential도 가능)로 한 번에 증명하는 circuit
+    // 모든 credential은 이 Holder의 공개 키로 발급받은 것이어야 함
+    pub fn multi_credential_circuit<H: CredentialHasher>(
+        &self,
+        today: Date,
+        claims: Vec<CredentialClaim<H>>,
+        scope: F,
+        challenge: F,
+    ) -> Result<MultiCredentialCircuit<H>, Error> {
+        if claims.is_empty() {
+            return Err(Error::NoCredentials);
+        }
+        if claims
+            .iter()
+            .any(|claim| claim.credential.holder_public_key != self.key.public_key)
+        {
+            return Err(Error::HolderKeyMismatch);
+        }
+        Ok(MultiCredentialCircuit {
+            today,
+            claims,
+            scope,
+            challenge,
+            holder_secret_key: self.key.secret_key(),
+        })
+    }
+
+    // 증명 생성 전에 circuit을 합성하여 만족되지 않는 첫 constraint와 그 구간(예: "predicate check")을 찾음
+    // 모두 만족하면 `None`. 만족되지 않는 circuit으로 만든 증명은 검증에 실패함
+    pub fn diagnose<C: SectionedCircuit>(circuit: C) -> Result<Option<Unsatisfied>, Error> {
+        Ok(which_is_unsatisfied(circuit)?)
+    }
+
+    // 증명 전 확인: circuit이 만족되지 않으면 원인별 오류(`NotAMember`, `PredicateNotSatisfied`, `CredentialExpired` 등)
+    pub fn preflight<C: SectionedCircuit>(circuit: C) -> Result<(), Error> {
+        match Self::diagnose(circuit)? {
+            Some(unsatisfied) => Err(Error::unsatisfied(unsatisfied)),
+            None => Ok(()),
+        }
+    }
+
+    // `preflight`로 확인한 뒤 증명 생성. 검증에 실패할 증명을 만드는 데 시간을 쓰지 않음
+    // `AgeCircuit`과 `MultiCredentialCircuit` 모두 사용 가능
+    pub fn prove_checked<C: SectionedCircuit + Clone>(
         proving_key: Groth16ProvingKey,
-        age_circuit: AgeCircuit,
-    ) -> Result<Groth16Proof, HolderError> {
+        circuit: C,
+    ) -> Result<Groth16Proof, Error> {
+        Self::preflight(circuit.clone())?;
+        Groth16::<Bn254>::prove(&proving_key, circuit, &mut ark_std::rand::thread_rng())
+            .map_err(Error::Prove)
+    }
+
+    // Verifier의 증명 요청에 답하는 presentation 생성
+    // wallet에서 요청을 만족하는 credential을 골라 `prove_checked`로 증명
+    pub fn present<H: CredentialHasher>(
+        wallet: &Wallet,
+        proving_key: Groth16ProvingKey,
+        request: &PresentationRequest,
+        revocations: &RevocationLists,
+    ) -> Result<Presentation, Error> {
+        let circuit = wallet.circuit::<H>(request, revocations)?;
+        let disclosed = request.disclosure.reveal(&circuit.credential)?;
+        let revealed_issuer = request
+            .reveal_issuer
+            .then_some(circuit.credential.issuer_id);
+        let public_inputs = circuit.public_inputs()?;
+        let proof = Self::prove_checked(proving_key, circuit)?;
+
+        Ok(Presentation {
+            verifier_id: request.verifier_id,
+            challenge: request.challenge,
+            proof,
+            public_inputs,
+            disclosed,
+            revealed_issuer,
+        })
+    }
+
+    pub fn prove<H: CredentialHasher>(
+        proving_key: Groth16ProvingKey,
+        age_circuit: AgeCircuit<H>,
+    ) -> Result<Groth16Proof, Error> {
         Groth16::<Bn254>::prove(&proving_key, age_circuit, &mut ark_std::rand::thread_rng())
-            .map_err(|_| HolderError::ProveFailed)
+            .map_err(Error::Prove)
     }
-}
 
-#[derive(Debug, Clone)]
-pub enum HolderError {
-    ProveFailed,
+    // `Verifier::setup_multi`의 proving key로 여러 credential의 증명 생성
+    pub fn prove_multi<H: CredentialHasher>(
+        proving_key: Groth16ProvingKey,
+        circuit: MultiCredentialCircuit<H>,
+    ) -> Result<Groth16Proof, Error> {
+        Groth16::<Bn254>::prove(&proving_key, circuit, &mut ark_std::rand::thread_rng())
+            .map_err(Error::Prove)
+    }
 }
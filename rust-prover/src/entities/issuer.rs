@@ -1,52 +1,120 @@
+use ark_ed_on_bn254::EdwardsAffine;
+
 use crate::{
-    MAX_CREDENTIALS, Sha256Digest, data_structures::credential::Credential,
+    F,
+    data_structures::{
+        credential::Credential,
+        hasher::CredentialHasher,
+        issuer_set::TrustedIssuer,
+        merkle_tree::{credential_root, tree_capacity},
+        revocation::revocation_root,
+        schema::Schema,
+        signature::{CredentialSignature, IssuerSigningKey},
+    },
+    error::Error,
     utils::utils::string_to_bytes,
 };
 
-pub struct Issuer {
+// H: Issuer가 발급하는 credential의 해시 방식
+pub struct Issuer<H: CredentialHasher> {
     pub id: [u8; 32],
-    credentials: Vec<Credential>, // Issuer가 발급한 모든 Credential. MAX_CREDENTIALS로 제한
-    hashed_credentials: Vec<Sha256Digest>, // Issuer가 발급한 Credential의 SHA256 해시 리스트. MAX_CREDENTIALS로 제한
+    pub tree_height: usize, // credential tree의 높이. 최대 2^(tree_height - 1)개의 credential 발급 가능
+    pub schema: Schema,     // Issuer가 발급하는 credential의 속성 목록
+    credentials: Vec<Credential>, // Issuer가 발급한 모든 Credential
+    hashed_credentials: Vec<H::Digest>, // Issuer가 발급한 Credential의 해시 리스트 (credential tree의 leaf)
+    revoked: Vec<usize>, // 폐기한 credential 번호 (credential tree에서의 index) 리스트
+    signing_key: IssuerSigningKey, // credential 서명 키. 공개 키만 공개
 }
 
-impl Issuer {
-    pub fn new(id: &str) -> Self {
-        Issuer {
-            id: string_to_bytes(id),
+impl<H: CredentialHasher> Issuer<H> {
+    // id는 UTF-8로 MAX_STRING_BYTES바이트 이하
+    pub fn new(id: &str, tree_height: usize, schema: Schema) -> Result<Self, Error> {
+        Ok(Issuer {
+            id: string_to_bytes(id).map_err(|source| Error::InvalidId {
+                id: id.to_string(),
+                source,
+            })?,
+            tree_height,
+            schema,
             credentials: vec![],
             hashed_credentials: vec![],
-        }
+            revoked: vec![],
+            signing_key: IssuerSigningKey::generate(&mut ark_std::rand::thread_rng()),
+        })
     }
 
-    // Credential 발급. 최대 MAX_CREDENTIALS 개까지 발급 가능. hashed_credentials에 credential의 SHA256 해시 저장 후 publish
-    pub fn issue_credential(&mut self, cred: &Credential) -> Result<(), IssuerError> {
-        if self.credentials.len() >= MAX_CREDENTIALS {
-            return Err(IssuerError::MaxCredentialsReached);
+    // Credential 발급. credential tree가 가득 차면 발급 불가. hashed_credentials에 credential의 해시 저장 후 publish
+    // Issuer의 schema로 만든 credential만 발급
+    // credential과 credential 번호(발급 순서)에 대한 서명을 Holder에게 전달. 서명으로 증명하면 해시 리스트는 필요 없음
+    pub fn issue_credential(&mut self, cred: &Credential) -> Result<CredentialSignature, Error> {
+        if cred.schema != self.schema {
+            return Err(Error::SchemaMismatch);
+        }
+        let capacity = tree_capacity(self.tree_height);
+        if self.credentials.len() >= capacity {
+            return Err(Error::CredentialTreeFull {
+                tree_height: self.tree_height,
+                capacity,
+            });
         }
 
+        let serial = self.credentials.len() as u64;
         self.credentials.push(cred.clone());
-        let hashed_credential = cred.to_sha256();
+        let hashed_credential = H::hash(cred);
         self.hashed_credentials.push(hashed_credential);
-        Ok(())
+        Ok(self.signing_key.sign(cred, serial))
     }
 
-    pub fn credentials(&self) -> Result<Vec<Credential>, IssuerError> {
-        if self.credentials.len() != MAX_CREDENTIALS {
-            return Err(IssuerError::IncompleteList);
-        }
-        Ok(self.credentials.clone())
+    // credential 서명을 검증하는 공개 키. circuit의 public input으로 사용
+    pub fn public_key(&self) -> EdwardsAffine {
+        self.signing_key.public_key
     }
 
-    pub fn hashed_credentials(&self) -> Result<Vec<Sha256Digest>, IssuerError> {
-        if self.hashed_credentials.len() != MAX_CREDENTIALS {
-            return Err(IssuerError::IncompleteList);
+    // Verifier가 issuer set에 등록하는 id, 공개 키와 현재 revocation root
+    pub fn trusted_issuer(&self) -> Result<TrustedIssuer, Error> {
+        Ok(TrustedIssuer {
+            id: self.id,
+            public_key: self.public_key(),
+            revocation_root: self.revocation_root()?,
+        })
+    }
+
+    pub fn credentials(&self) -> Vec<Credential> {
+        self.credentials.clone()
+    }
+
+    pub fn hashed_credentials(&self) -> Vec<H::Digest> {
+        self.hashed_credentials.clone()
+    }
+
+    // 발급한 credential 해시들로 만든 Merkle tree의 root. circuit의 public input으로 사용
+    pub fn credential_root(&self) -> Result<H::Digest, Error> {
+        credential_root::<H>(self.tree_height, &self.hashed_credentials)
+            .map_err(Error::credential_tree)
+    }
+
+    // Credential 폐기. 발급한 credential만 폐기 가능. 폐기한 credential 번호를 revocation tree에 반영 후 publish
+    pub fn revoke(&mut self, cred: &Credential) -> Result<(), Error> {
+        let hashed_credential = H::hash(cred);
+        let index = self
+            .hashed_credentials
+            .iter()
+            .position(|h| *h == hashed_credential)
+            .ok_or(Error::CredentialNotIssued)?;
+        if self.revoked.contains(&index) {
+            return Err(Error::AlreadyRevoked);
         }
-        Ok(self.hashed_credentials.clone())
+
+        self.revoked.push(index);
+        Ok(())
+    }
+
+    pub fn revoked_indices(&self) -> Vec<usize> {
+        self.revoked.clone()
     }
-}
 
-#[derive(Debug, Clone)]
-pub enum IssuerError {
-    MaxCredentialsReached, // Issuer가 발급할 수 있는 Credential의 최대 개수 도달
-    IncompleteList,        // 리스트의 원소 개수 부족
+    // 폐기한 credential 번호들로 만든 revocation tree의 root. circuit의 public input으로 사용
+    pub fn revocation_root(&self) -> Result<F, Error> {
+        revocation_root(self.tree_height, &self.revoked)
+    }
 }
@@ -1,48 +1,356 @@
 use ark_bn254::Bn254;
-use ark_crypto_primitives::snark::CircuitSpecificSetupSNARK;
+use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+use ark_ed_on_bn254::Fr as EdFr;
+use ark_ff::{One, Zero};
 use ark_groth16::Groth16;
-use ark_std::test_rng;
-use rand::{RngCore, SeedableRng};
+use rand::{CryptoRng, RngCore, rngs::OsRng};
+#[cfg(test)]
+use rand::{SeedableRng, rngs::StdRng};
 
 use crate::{
-    Groth16ProvingKey, Groth16VerifyingKey,
-    data_structures::{circuit::AgeCircuit, credential::Credential},
+    F, Groth16Proof, Groth16ProvingKey, Groth16VerifyingKey,
+    data_structures::{
+        challenge::ChallengeStore,
+        circuit::{AgeCircuit, CredentialClaim, Issuance, IssuanceMode, PublicIssuance},
+        credential::{Credential, CredentialEncoding},
+        date::Date,
+        disclosure::{DisclosedValues, Disclosure},
+        hasher::CredentialHasher,
+        issuer_set::{IssuerSet, TrustedIssuer},
+        merkle_tree::{credential_root, membership_path},
+        multi_credential::{ClaimShape, MultiCredentialCircuit},
+        nullifier::{NullifierStore, scope_from_id},
+        policy::{Policy, Submitted},
+        predicate::Predicate,
+        presentation::{Presentation, PresentationRequest},
+        revocation::{RevocationLists, non_revocation_path, revocation_root},
+        schema::Schema,
+        signature::IssuerSigningKey,
+    },
+    error::Error,
     utils::utils::string_to_bytes,
 };
 
 pub struct Verifier {
     pub id: [u8; 32],
+    nullifiers: NullifierStore, // 이미 받은 증명의 nullifier
+    challenges: ChallengeStore, // 발급했지만 아직 증명을 받지 않은 challenge
 }
 
 impl Verifier {
-    pub fn new(id: &str) -> Self {
-        Verifier {
-            id: string_to_bytes(id),
-        }
-    }
-    pub fn setup(&self) -> Result<(Groth16ProvingKey, Groth16VerifyingKey), VerifierError> {
-        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
-
-        let mock_circuit = AgeCircuit {
-            dob_cutoff_year: "0".to_string(),
-            hashed_credentials: vec![vec![0u8; 32]; crate::MAX_CREDENTIALS]
-                .try_into()
-                .expect("Wrong length"),
-            credential: Credential::new(
-                [0u8; 32],
-                "0".to_string(),
-                "0".to_string(),
-                "0".to_string(),
-            ),
+    // id는 UTF-8로 MAX_STRING_BYTES바이트 이하
+    pub fn new(id: &str) -> Result<Self, Error> {
+        Ok(Verifier {
+            id: string_to_bytes(id).map_err(|source| Error::InvalidId {
+                id: id.to_string(),
+                source,
+            })?,
+            nullifiers: NullifierStore::new(),
+            challenges: ChallengeStore::new(),
+        })
+    }
+
+    // 이 Verifier에게 제출하는 증명의 scope. Verifier id로 결정
+    pub fn scope(&self) -> F {
+        scope_from_id(&self.id)
+    }
+
+    // 증명의 nullifier 기록. 같은 nullifier가 다시 제출되면 (재전송, 같은 credential의 중복 제출) 거부
+    pub fn accept_nullifier(&mut self, nullifier: F) -> Result<(), Error> {
+        self.nullifiers.insert(nullifier)
+    }
+
+    // 증명 요청마다 새 challenge 발급. Holder는 이 challenge로 증명을 만들어 제출
+    pub fn new_challenge(&mut self) -> F {
+        self.challenges.issue(&mut ark_std::rand::thread_rng())
+    }
+
+    // 증명의 challenge 확인. 발급하지 않았거나 이미 사용한 challenge(다른 요청에서 가져온 증명)는 거부
+    pub fn accept_challenge(&mut self, challenge: F) -> Result<(), Error> {
+        self.challenges.consume(challenge)
+    }
+
+    // Holder가 제출한 공개 속성 값을 schema에 따라 다시 인코딩하여 circuit의 public input으로 만듦
+    // 요청한 속성과 다르거나 schema의 타입에 맞지 않는 값은 거부
+    pub fn disclosed_inputs(
+        &self,
+        schema: &Schema,
+        disclosure: &Disclosure,
+        disclosed: &DisclosedValues,
+    ) -> Result<Vec<F>, Error> {
+        disclosure.public_inputs(schema, disclosed)
+    }
+
+    // circuit의 구조는 발급 확인 방식(credential 해시 방식과 root의 public input 방식, 서명, 또는 issuer set과 issuer_id 공개 여부),
+    // credential / revocation tree의 높이, credential의 schema와 할당 방식, 조건식의 모양과 공개할 속성에 따라 달라짐. 조건식의 값은 public input이므로 setup에 영향을 주지 않음
+    // toxic waste는 OS 난수로 만들고 키 생성 후 버림
+    pub fn setup<H: CredentialHasher>(
+        &self,
+        tree_height: usize,
+        mode: IssuanceMode,
+        schema: &Schema,
+        predicate: &Predicate,
+        disclosure: &Disclosure,
+        encoding: CredentialEncoding,
+    ) -> Result<(Groth16ProvingKey, Groth16VerifyingKey), Error> {
+        let shape = ClaimShape {
+            mode,
+            schema: schema.clone(),
+            predicate: predicate.clone(),
+            disclosure: disclosure.clone(),
+            encoding,
+        };
+        self.setup_claim::<H, _>(tree_height, &shape, &mut OsRng)
+    }
+
+    /// 테스트 전용 setup. 같은 seed로 같은 키를 만듦
+    /// seed를 아는 사람은 toxic waste를 다시 만들어 증명을 위조할 수 있으므로 실제 검증에 사용하면 안 됨
+    #[cfg(test)]
+    pub fn setup_deterministic<H: CredentialHasher>(
+        &self,
+        tree_height: usize,
+        shape: &ClaimShape,
+        seed: u64,
+    ) -> Result<(Groth16ProvingKey, Groth16VerifyingKey), Error> {
+        self.setup_claim::<H, _>(tree_height, shape, &mut StdRng::seed_from_u64(seed))
+    }
+
+    // mock credential의 `AgeCircuit`으로 키 생성. toxic waste는 `Groth16::setup` 안에서만 사용되고 저장하지 않음
+    fn setup_claim<H: CredentialHasher, R: RngCore + CryptoRng>(
+        &self,
+        tree_height: usize,
+        shape: &ClaimShape,
+        rng: &mut R,
+    ) -> Result<(Groth16ProvingKey, Groth16VerifyingKey), Error> {
+        let claim = mock_claim::<H>(tree_height, shape)?;
+
+        let mock_circuit = AgeCircuit::<H> {
+            today: Date::UNIX_EPOCH,
+            predicate: claim.predicate,
+            disclosure: claim.disclosure,
+            issuance: claim.issuance,
+            holder_secret_key: F::zero(),
+            credential: claim.credential,
+            revocation_root: claim.revocation_root,
+            non_revocation_path: claim.non_revocation_path,
+            encoding: claim.encoding,
+            scope: self.scope(),
+            challenge: F::zero(),
         };
 
-        let (pk, vk) = Groth16::<Bn254>::setup(mock_circuit, &mut rng)
-            .map_err(|_| VerifierError::SetupFailed)?;
+        let (pk, vk) = Groth16::<Bn254>::setup(mock_circuit, rng).map_err(Error::Setup)?;
+        Ok((pk, vk))
+    }
+
+    // 증명 요청에 답하는 circuit의 setup. 요청의 tree 높이, 발급 확인 방식, schema, 조건식, 공개할 속성, 할당 방식을 사용
+    pub fn setup_request<H: CredentialHasher>(
+        &self,
+        request: &PresentationRequest,
+    ) -> Result<(Groth16ProvingKey, Groth16VerifyingKey), Error> {
+        self.setup::<H>(
+            request.tree_height,
+            request.mode(),
+            &request.schema,
+            &request.predicate,
+            &request.disclosure,
+            request.encoding,
+        )
+    }
+
+    /// `AgeCircuit`의 public input을 circuit이 할당하는 순서로 만듦
+    /// Verifier가 정한 조건과 발급 정보, Issuer가 공개한 revocation root, Holder가 제출한 값을 사용
+    pub fn public_inputs<H: CredentialHasher>(
+        &self,
+        policy: &Policy,
+        issuance: &PublicIssuance<H>,
+        revocation_root: F,
+        submitted: &Submitted,
+    ) -> Result<Vec<F>, Error> {
+        policy.public_inputs(issuance, revocation_root, self.scope(), submitted)
+    }
+
+    /// `AgeCircuit`의 증명 검증. public input은 `public_inputs`로 만듦
+    /// 발급하지 않았거나 이미 사용한 challenge는 `UnknownChallenge`, 검증되지 않는 증명은 `InvalidProof`
+    /// 검증에 성공하면 challenge와 nullifier를 기록하여 같은 요청이나 같은 Holder의 증명은 다시 받지 않음
+    pub fn verify<H: CredentialHasher>(
+        &mut self,
+        verifying_key: &Groth16VerifyingKey,
+        policy: &Policy,
+        issuance: &PublicIssuance<H>,
+        revocation_root: F,
+        submitted: &Submitted,
+        proof: &Groth16Proof,
+    ) -> Result<(), Error> {
+        if !self.challenges.is_pending(&policy.challenge) {
+            return Err(Error::UnknownChallenge);
+        }
+        let public_inputs = self.public_inputs(policy, issuance, revocation_root, submitted)?;
+        let is_valid = Groth16::<Bn254>::verify(verifying_key, &public_inputs, proof)
+            .map_err(Error::Verify)?;
+        if !is_valid {
+            return Err(Error::InvalidProof);
+        }
+        self.accept_challenge(policy.challenge)?;
+        self.accept_nullifier(submitted.nullifier)
+    }
+
+    /// 증명 요청에 대한 Holder의 presentation 검증
+    /// public input은 요청과 제출된 공개 값으로 다시 만들고, Holder만 계산할 수 있는 값(revocation root, challenge binding, nullifier)만 제출된 값을 사용
+    /// issuer set에 등록된 revocation root는 모두 Issuer가 공개한 폐기 리스트의 현재 root여야 함
+    /// 제출된 revocation root는 신뢰하는 Issuer(issuer_id를 공개했으면 그 Issuer)의 root여야 하고,
+    /// circuit은 그 root가 credential을 발급한 Issuer의 leaf에 있는 root인지 확인
+    pub fn check_presentation<H: CredentialHasher>(
+        &mut self,
+        verifying_key: &Groth16VerifyingKey,
+        request: &PresentationRequest,
+        presentation: &Presentation,
+        revocations: &RevocationLists,
+    ) -> Result<(), Error> {
+        if request.verifier_id != self.id
+            || presentation.verifier_id != self.id
+            || presentation.challenge != request.challenge
+            || request.reveal_issuer != presentation.revealed_issuer.is_some()
+        {
+            return Err(Error::PresentationMismatch);
+        }
+
+        // 마지막 public input: revocation root, scope, challenge, challenge binding, nullifier
+        let Some(
+            &[
+                presented_revocation_root,
+                _,
+                _,
+                challenge_binding,
+                nullifier,
+            ],
+        ) = presentation.public_inputs.last_chunk::<5>()
+        else {
+            return Err(Error::PresentationMismatch);
+        };
+        let is_current_set = request.issuers.issuers().iter().all(|issuer| {
+            revocations.get(&issuer.id).is_some_and(|revoked| {
+                revocation_root(request.tree_height, revoked)
+                    .is_ok_and(|root| root == issuer.revocation_root)
+            })
+        });
+        let is_trusted_root = request
+            .issuers
+            .issuers()
+            .iter()
+            .filter(|issuer| {
+                presentation
+                    .revealed_issuer
+                    .is_none_or(|issuer_id| issuer_id == issuer.id)
+            })
+            .any(|issuer| issuer.revocation_root == presented_revocation_root);
+        if !is_current_set || !is_trusted_root {
+            return Err(Error::UnknownRevocationRoot);
+        }
+
+        let policy = request.policy();
+        let issuance = PublicIssuance::<H>::TrustedIssuers {
+            issuer_set_root: request.issuers.root()?,
+            revealed_issuer: presentation.revealed_issuer,
+        };
+        let submitted = Submitted {
+            disclosed: presentation.disclosed.clone(),
+            challenge_binding,
+            nullifier,
+        };
+        if self.public_inputs(&policy, &issuance, presented_revocation_root, &submitted)?
+            != presentation.public_inputs
+        {
+            return Err(Error::PresentationMismatch);
+        }
+        self.verify(
+            verifying_key,
+            &policy,
+            &issuance,
+            presented_revocation_root,
+            &submitted,
+            &presentation.proof,
+        )
+    }
+
+    // 여러 credential로 한 번에 증명하는 `MultiCredentialCircuit`의 setup
+    // circuit의 구조는 credential마다의 모양(`ClaimShape`)과 순서에 따라 달라짐
+    // toxic waste는 `setup`과 같이 OS 난수로 만들고 키 생성 후 버림
+    pub fn setup_multi<H: CredentialHasher>(
+        &self,
+        tree_height: usize,
+        shapes: &[ClaimShape],
+    ) -> Result<(Groth16ProvingKey, Groth16VerifyingKey), Error> {
+        if shapes.is_empty() {
+            return Err(Error::NoCredentials);
+        }
+        let claims = shapes
+            .iter()
+            .map(|shape| mock_claim::<H>(tree_height, shape))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mock_circuit = MultiCredentialCircuit::<H> {
+            today: Date::UNIX_EPOCH,
+            claims,
+            scope: self.scope(),
+            challenge: F::zero(),
+            holder_secret_key: F::zero(),
+        };
+
+        let (pk, vk) = Groth16::<Bn254>::setup(mock_circuit, &mut OsRng).map_err(Error::Setup)?;
         Ok((pk, vk))
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum VerifierError {
-    SetupFailed,
+// setup에서 circuit의 구조를 정하는 데만 사용하는 credential 하나의 claim
+fn mock_claim<H: CredentialHasher>(
+    tree_height: usize,
+    shape: &ClaimShape,
+) -> Result<CredentialClaim<H>, Error> {
+    shape.predicate.check(&shape.schema)?;
+    shape.disclosure.check(&shape.schema)?;
+
+    let credential = Credential::mock(&shape.schema);
+    let issuance = match shape.mode {
+        IssuanceMode::Published(root_encoding) => Issuance::Published {
+            credential_root: credential_root::<H>(tree_height, &[])
+                .map_err(Error::credential_tree)?,
+            root_encoding,
+            membership_path: membership_path::<H>(tree_height, &[], 0)
+                .map_err(Error::credential_tree)?,
+        },
+        IssuanceMode::Signed => {
+            let mock_key = IssuerSigningKey::new(EdFr::one());
+            Issuance::Signed {
+                issuer_public_key: mock_key.public_key,
+                signature: mock_key.sign(&credential, 0),
+            }
+        }
+        IssuanceMode::TrustedIssuers { reveal_issuer } => {
+            let mock_key = IssuerSigningKey::new(EdFr::one());
+            let issuer_set = IssuerSet::new(vec![TrustedIssuer {
+                id: credential.issuer_id,
+                public_key: mock_key.public_key,
+                revocation_root: revocation_root(tree_height, &[])?,
+            }])?;
+            let (issuer_public_key, issuer_path) =
+                issuer_set.membership_path(&credential.issuer_id)?;
+            Issuance::TrustedIssuers {
+                issuer_set_root: issuer_set.root()?,
+                revealed_issuer: reveal_issuer.then_some(credential.issuer_id),
+                issuer_public_key,
+                issuer_path,
+                signature: mock_key.sign(&credential, 0),
+            }
+        }
+    };
+
+    Ok(CredentialClaim {
+        predicate: shape.predicate.clone(),
+        disclosure: shape.disclosure.clone(),
+        issuance,
+        revocation_root: revocation_root(tree_height, &[])?,
+        credential,
+        non_revocation_path: non_revocation_path(tree_height, &[], 0)?,
+        encoding: shape.encoding,
+    })
 }
@@ -0,0 +1,128 @@
+use ark_relations::r1cs::SynthesisError;
+
+use crate::{
+    data_structures::{
+        date::DateError,
+        diagnostics::{Section, Unsatisfied},
+        predicate::PredicateError,
+        schema::SchemaError,
+    },
+    utils::utils::StringError,
+};
+
+/// crate 전체에서 사용하는 오류
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    // -------------------- 입력 --------------------
+    #[error("invalid id {id:?}: {source}")]
+    InvalidId { id: String, source: StringError },
+    #[error(transparent)]
+    Encoding(#[from] StringError),
+    #[error("invalid date: {0}")]
+    Date(#[from] DateError),
+    #[error("credential does not match its schema: {0}")]
+    Schema(#[from] SchemaError),
+    #[error("invalid predicate: {0}")]
+    Predicate(#[from] PredicateError),
+
+    // -------------------- credential 발급 / 폐기 / Merkle tree --------------------
+    #[error("credential tree of height {tree_height} is full ({capacity} credentials)")]
+    CredentialTreeFull { tree_height: usize, capacity: usize },
+    #[error("credential was built with a schema other than the issuer's")]
+    SchemaMismatch,
+    #[error("credential was issued to a different holder key")]
+    HolderKeyMismatch,
+    #[error("credential is not in the published credential list")]
+    CredentialNotPublished,
+    #[error("credential was not issued by this issuer")]
+    CredentialNotIssued,
+    #[error("credential has been revoked by its issuer")]
+    CredentialRevoked,
+    #[error("credential is already revoked")]
+    AlreadyRevoked,
+    #[error("credential issuer is not in the trusted issuer set")]
+    UntrustedIssuer,
+    #[error("trusted issuer set is full ({capacity} issuers)")]
+    IssuerSetFull { capacity: usize },
+    #[error("a multi-credential proof needs at least one credential")]
+    NoCredentials,
+    #[error("no credential in the wallet satisfies the presentation request")]
+    NoMatchingCredential,
+    #[error("credential tree construction failed: {0}")]
+    CredentialTree(String),
+
+    // -------------------- circuit / Groth16 --------------------
+    #[error("constraint synthesis failed: {0}")]
+    Synthesis(#[from] SynthesisError),
+    #[error("circuit setup failed: {0}")]
+    Setup(SynthesisError),
+    #[error("proof generation failed: {0}")]
+    Prove(SynthesisError),
+    #[error("proof verification failed: {0}")]
+    Verify(SynthesisError),
+
+    // -------------------- 증명 전 확인 --------------------
+    #[error("credential (or its issuer) is not a member of the committed Merkle tree")]
+    NotAMember,
+    #[error("issuer signature on the credential does not verify")]
+    InvalidSignature,
+    #[error("credential is expired or not yet valid on the proof date")]
+    CredentialExpired,
+    #[error("credential does not satisfy the predicate (e.g. holder is too young)")]
+    PredicateNotSatisfied,
+    #[error("circuit is not satisfied: {0}")]
+    Unsatisfied(Unsatisfied),
+
+    // -------------------- 증명 제출 --------------------
+    #[error("nullifier was already presented to this verifier")]
+    NullifierReused,
+    #[error("challenge was not issued by this verifier or was already used")]
+    UnknownChallenge,
+    #[error("disclosed values do not match the requested disclosure")]
+    DisclosureMismatch,
+    #[error("presentation does not answer the presentation request")]
+    PresentationMismatch,
+    #[error("revocation root is not the current root of a trusted issuer")]
+    UnknownRevocationRoot,
+    #[error("proof does not verify against the verifying key")]
+    InvalidProof,
+
+    // -------------------- 저장 / 직렬화 --------------------
+    #[error("file access failed: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization failed: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    // -------------------- on-chain 검증 --------------------
+    #[error("environment variable {0} is not set")]
+    MissingEnv(&'static str),
+    #[error("chain interaction failed: {0}")]
+    Chain(String),
+}
+
+impl Error {
+    // ethers의 여러 오류 타입을 `Chain`으로 변환
+    pub fn chain(error: impl std::fmt::Display) -> Self {
+        Error::Chain(error.to_string())
+    }
+
+    // 만족되지 않는 constraint의 구간을 원인별 오류로 변환. 원인을 알 수 없으면 `Unsatisfied`
+    // 여러 credential 증명에서는 가장 안쪽 구간으로 판단 (어느 credential인지는 `Holder::diagnose`로 확인)
+    pub fn unsatisfied(unsatisfied: Unsatisfied) -> Self {
+        match unsatisfied.path.last() {
+            Some(Section::Membership) => Error::NotAMember,
+            Some(Section::Signature) => Error::InvalidSignature,
+            Some(Section::Revocation) => Error::CredentialRevoked,
+            Some(Section::Validity) => Error::CredentialExpired,
+            Some(Section::Predicate) => Error::PredicateNotSatisfied,
+            Some(Section::Disclosure) => Error::DisclosureMismatch,
+            Some(Section::HolderKey) => Error::HolderKeyMismatch,
+            _ => Error::Unsatisfied(unsatisfied),
+        }
+    }
+
+    // ark-crypto-primitives의 Merkle tree 오류를 `CredentialTree`로 변환
+    pub fn credential_tree(error: ark_crypto_primitives::Error) -> Self {
+        Error::CredentialTree(error.to_string())
+    }
+}
@@ -1,18 +1,31 @@
 use crate::{
-    data_structures::{circuit::AgeCircuit, credential::Credential},
+    data_structures::{
+        credential::{Credential, CredentialEncoding},
+        date::{Date, Validity},
+        disclosure::Disclosure,
+        hasher::Sha256Hasher,
+        holder_key::HolderKey,
+        issuer_set::IssuerSet,
+        predicate::Predicate,
+        presentation::{Presentation, PresentationRequest},
+        revocation::RevocationLists,
+        schema::{AttributeType, Schema, Value},
+        signature::verify_signature,
+        wallet::Wallet,
+    },
     entities::{holder::Holder, issuer::Issuer, verifier::Verifier},
+    error::Error,
     utils::solidity::ToSolidity,
 };
 use ark_bn254::Bn254;
 use ark_crypto_primitives::snark::SNARK;
-use ark_ec::pairing::Pairing;
 use ark_groth16::Groth16;
-use ark_std::{One, Zero};
 use ethers::prelude::*;
-use std::{str::FromStr, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 pub mod data_structures;
 pub mod entities;
+pub mod error;
 pub mod utils;
 
 type F = ark_bn254::Fr;
@@ -21,16 +34,312 @@ type Groth16Proof = <Groth16<Bn254> as SNARK<F>>::Proof;
 type Groth16ProvingKey = <Groth16<Bn254> as SNARK<F>>::ProvingKey;
 type Groth16VerifyingKey = <Groth16<Bn254> as SNARK<F>>::VerifyingKey;
 
-const MAX_CREDENTIALS: usize = 3;
-const CUTOFF_YEAR: &'static str = "2006"; // 성인 연령 기준 연도
+const CREDENTIAL_TREE_HEIGHT: usize = 12; // credential tree의 높이. 최대 2^11 = 2048개의 credential 발급 가능
+const NUM_CREDENTIALS: usize = 3; // 예제에서 발급하는 credential 개수
+const HOLDER_NAMES: [&str; NUM_CREDENTIALS] = ["김민수", "Alice", "José Álvarez"]; // 예제 holder의 이름 (UTF-8)
+const MIN_AGE: u32 = 19; // 성인 연령 기준 (만 나이)
+const CREDENTIAL_VALIDITY_YEARS: u32 = 5; // 예제 credential의 유효 기간. 발급일부터 5년 뒤 연말까지
+const HOLDER_NAME: &str = "holder_name"; // 예제 schema의 이름 속성
+const HOLDER_DOB: &str = "holder_dob"; // 예제 schema의 생년월일 속성
 
 abigen!(Groth16Verifier, "./abi.json");
 
+async fn send_tx(
+    proof: Vec<String>,
+    public_inputs: Vec<String>,
+    vk: Vec<String>,
+    contract_address: Address,
+) -> Result<(), Error> {
+    dotenv::dotenv().ok();
+    let rpc_url = std::env::var("RPC_URL").map_err(|_| Error::MissingEnv("RPC_URL"))?;
+    let private_key = std::env::var("PRIVATE_KEY").map_err(|_| Error::MissingEnv("PRIVATE_KEY"))?;
+
+    let provider = Provider::<Http>::try_from(rpc_url)
+        .map_err(Error::chain)?
+        .interval(Duration::from_millis(10u64));
+    let chain_id = provider.get_chainid().await.map_err(Error::chain)?;
+
+    let wallet = private_key
+        .parse::<LocalWallet>()
+        .map_err(Error::chain)?
+        .with_chain_id(chain_id.as_u64());
+    let client = SignerMiddleware::new(provider, wallet.clone());
+    let client = Arc::new(client);
+
+    let contract = Groth16Verifier::new(contract_address, client.clone());
+
+    let mut proof_uints: [U256; 8] = [U256::zero(); 8];
+    for (proof_uint, p) in proof_uints.iter_mut().zip(&proof) {
+        *proof_uint = U256::from_str_radix(p, 10).map_err(Error::chain)?;
+    }
+
+    // public input 개수는 circuit에 따라 달라지므로 길이를 고정하지 않음
+    // 예제: 2 (기준 날짜, 만 나이 기준) + 2 (issuer set root, issuer_id) + 5 (revocation root, scope, challenge, challenge binding, nullifier) = 9
+    let mut inputs: Vec<U256> = Vec::with_capacity(public_inputs.len());
+    for input in &public_inputs {
+        inputs.push(U256::from_str_radix(input, 10).map_err(Error::chain)?);
+    }
+
+    let vk_alpha1 = G1Point {
+        x: U256::from_str_radix(&vk[0], 10).map_err(Error::chain)?,
+        y: U256::from_str_radix(&vk[1], 10).map_err(Error::chain)?,
+    };
+    let vk_beta2 = G2Point {
+        x: [
+            U256::from_str_radix(&vk[2], 10).map_err(Error::chain)?,
+            U256::from_str_radix(&vk[3], 10).map_err(Error::chain)?,
+        ],
+        y: [
+            U256::from_str_radix(&vk[4], 10).map_err(Error::chain)?,
+            U256::from_str_radix(&vk[5], 10).map_err(Error::chain)?,
+        ],
+    };
+    let vk_gamma2 = G2Point {
+        x: [
+            U256::from_str_radix(&vk[6], 10).map_err(Error::chain)?,
+            U256::from_str_radix(&vk[7], 10).map_err(Error::chain)?,
+        ],
+        y: [
+            U256::from_str_radix(&vk[8], 10).map_err(Error::chain)?,
+            U256::from_str_radix(&vk[9], 10).map_err(Error::chain)?,
+        ],
+    };
+    let vk_delta2 = G2Point {
+        x: [
+            U256::from_str_radix(&vk[10], 10).map_err(Error::chain)?,
+            U256::from_str_radix(&vk[11], 10).map_err(Error::chain)?,
+        ],
+        y: [
+            U256::from_str_radix(&vk[12], 10).map_err(Error::chain)?,
+            U256::from_str_radix(&vk[13], 10).map_err(Error::chain)?,
+        ],
+    };
+
+    let mut vk_public_input_unconverted = vec![];
+    for i in (14..vk.len()).step_by(2) {
+        vk_public_input_unconverted.push([
+            U256::from_str_radix(&vk[i], 10).map_err(Error::chain)?,
+            U256::from_str_radix(&vk[i + 1], 10).map_err(Error::chain)?,
+        ]);
+    }
+    let vk_public_input: Vec<G1Point> = vk_public_input_unconverted
+        .into_iter()
+        .map(|p| G1Point { x: p[0], y: p[1] })
+        .collect();
+
+    if vk_public_input.len() != inputs.len() + 1 {
+        return Err(Error::Chain(format!(
+            "verifying key has {} public input points for {} public inputs",
+            vk_public_input.len(),
+            inputs.len()
+        )));
+    }
+
+    let verifying_key = VerifyingKey {
+        alpha_1: vk_alpha1,
+        beta_2: vk_beta2,
+        gamma_2: vk_gamma2,
+        delta_2: vk_delta2,
+        public_input: vk_public_input,
+    };
+
+    let tx = contract.verify_proof(proof_uints, inputs, verifying_key);
+    let pending_tx = tx.send().await.map_err(Error::chain)?;
+    let receipt = pending_tx
+        .await
+        .map_err(Error::chain)?
+        .ok_or_else(|| Error::Chain("transaction was dropped from the mempool".to_string()))?;
+
+    println!("Transaction receipt: {:?}", receipt);
+
+    let tx = contract.get_pairing_result();
+    let pairing_result: bool = tx.call().await.map_err(Error::chain)?;
+    println!("Pairing result from contract: {}", pairing_result);
+
+    Ok(())
+}
+
+// 예제에서 Issuer가 발급하는 credential의 schema: 이름과 생년월일
+fn person_schema() -> Schema {
+    Schema::new(vec![
+        (HOLDER_NAME, AttributeType::String),
+        (HOLDER_DOB, AttributeType::Date),
+    ])
+    .expect("person schema is valid")
+}
+
+// `person_schema`에 따른 credential
+fn person_credential(
+    issuer_id: [u8; 32],
+    holder_public_key: F,
+    validity: Validity,
+    holder_name: String,
+    holder_dob: Date,
+    randomness: F,
+) -> Result<Credential, Error> {
+    Ok(Credential::new(
+        issuer_id,
+        holder_public_key,
+        validity,
+        &person_schema(),
+        vec![
+            (HOLDER_NAME, Value::String(holder_name)),
+            (HOLDER_DOB, Value::Date(holder_dob)),
+        ],
+        randomness,
+    )?)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let mut issuer = Issuer::<Sha256Hasher>::new("1", CREDENTIAL_TREE_HEIGHT, person_schema())?;
+
+    // ------------------------------ Issuer ------------------------------
+    // credential 준비. 각 Holder가 만든 키 쌍의 공개 키로 발급
+    let issued_at = Date::today();
+    let validity = Validity::new(
+        issued_at,
+        Date::new(issued_at.year() + CREDENTIAL_VALIDITY_YEARS, 12, 31)?,
+    )?;
+    let holder_keys: Vec<HolderKey> = (0..NUM_CREDENTIALS)
+        .map(|_| HolderKey::generate(&mut ark_std::rand::thread_rng()))
+        .collect();
+    let mut credentials = Vec::new();
+    for (i, holder_key) in holder_keys.iter().enumerate() {
+        let cred = person_credential(
+            issuer.id,
+            holder_key.public_key,
+            validity,
+            HOLDER_NAMES[i].to_string(),
+            Date::new(2005 + i as u32, 6, 15)?, // 2005-06-15, 2006-06-15, ...
+            F::from(rand::random::<u128>()),
+        )?;
+        credentials.push(cred);
+    }
+
+    // Issuer의 credential 발급. Issuer는 credential마다 서명하여 Holder에게 전달
+    let mut signatures = Vec::new();
+    for cred in &credentials {
+        signatures.push(issuer.issue_credential(cred)?);
+    }
+
+    // 마지막 credential 폐기. 폐기한 credential로는 증명을 만들 수 없음
+    issuer.revoke(&credentials[NUM_CREDENTIALS - 1])?;
+
+    // Issuer가 publish하는 것은 서명 공개 키와 폐기한 credential 번호(폐기 리스트)뿐
+    let issuer_public_key = issuer.public_key();
+    println!(
+        "Issuer Public Key for Solidity: {:?}",
+        issuer_public_key.to_solidity()
+    );
+
+    // ------------------------------ Verifier ------------------------------
+    let mut verifier = Verifier::new("2")?;
+    // Verifier가 신뢰하는 Issuer들
+    let other_issuer = Issuer::<Sha256Hasher>::new("3", CREDENTIAL_TREE_HEIGHT, person_schema())?;
+    // 증명 요청. 오늘 날짜 기준으로 만 MIN_AGE세 이상인지 확인하고 생년월일은 숨기고 이름만 공개하도록 요구
+    // credential의 모든 값을 field 원소로 할당하여 native와 같은 인코딩인지 circuit이 확인
+    // 증명에서 credential을 발급한 Issuer의 id를 공개하도록 요구. challenge는 이번 요청에서만 유효
+    let request = PresentationRequest {
+        verifier_id: verifier.id,
+        challenge: verifier.new_challenge(),
+        today: Date::today(),
+        tree_height: CREDENTIAL_TREE_HEIGHT,
+        schema: person_schema(),
+        predicate: Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+        disclosure: Disclosure::none().with_attribute(HOLDER_NAME),
+        encoding: CredentialEncoding::Canonical,
+        issuers: IssuerSet::new(vec![
+            issuer.trusted_issuer()?,
+            other_issuer.trusted_issuer()?,
+        ])?,
+        reveal_issuer: true,
+    };
+    let (proving_key, verifying_key) = verifier.setup_request::<Sha256Hasher>(&request)?;
+    // Issuer들이 publish한 폐기 리스트
+    let revocations = RevocationLists::from([
+        (issuer.id, issuer.revoked_indices()),
+        (other_issuer.id, other_issuer.revoked_indices()),
+    ]);
+    // 증명 요청은 JSON으로 Holder에게 전달
+    let request_json = serde_json::to_string(&request)?;
+
+    // ------------------------------ Holder ------------------------------
+    // 2005년생 holder. Issuer에게서 받은 서명을 검증하고 wallet에 보관
+    let mut wallet = Wallet::new(holder_keys[0].clone());
+    assert!(verify_signature(
+        &issuer_public_key,
+        &credentials[0],
+        &signatures[0]
+    ));
+    wallet.insert(credentials[0].clone(), signatures[0].clone())?;
+
+    // 요청을 만족하는 credential을 찾아 증명 전에 circuit이 만족되는지 확인하고 증명 생성
+    let request: PresentationRequest = serde_json::from_str(&request_json)?;
+    let presentation =
+        Holder::present::<Sha256Hasher>(&wallet, proving_key, &request, &revocations)?;
+    let presentation_json = serde_json::to_string(&presentation)?;
+
+    // ------------------------------ Verifier ------------------------------
+    // 요청과 공개한 값으로 public input을 다시 만들어 검증
+    // 발급한 challenge의 증명만, 같은 nullifier의 증명은 한 번만 받음
+    let presentation: Presentation = serde_json::from_str(&presentation_json)?;
+    verifier.check_presentation::<Sha256Hasher>(
+        &verifying_key,
+        &request,
+        &presentation,
+        &revocations,
+    )?;
+
+    // ------------------------------ Verifier ------------------------------
+    // for solidity verifier contract
+    let vk_solidity = verifying_key.to_solidity();
+    println!("Verifying Key for Solidity: {:?}", vk_solidity);
+
+    let proof_solidity = presentation.proof.to_solidity();
+    println!("Proof for Solidity: {:?}", proof_solidity);
+
+    let public_inputs_solidity: Vec<String> = presentation.public_inputs.to_solidity();
+    println!("Public Inputs for Solidity: {:?}", public_inputs_solidity);
+
+    let contract_address = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
+        .parse::<Address>()
+        .map_err(Error::chain)?;
+
+    send_tx(
+        proof_solidity,
+        public_inputs_solidity,
+        vk_solidity,
+        contract_address,
+    )
+    .await
+}
+
 // SHA256 해시의 preimage를 증명하는 회로 테스트
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::utils::utils::*;
+    use crate::{
+        data_structures::{
+            challenge::challenge_binding,
+            circuit::{AgeCircuit, CredentialClaim, Issuance, IssuanceMode, PublicIssuance},
+            credential::CredentialVar,
+            date::DateError,
+            diagnostics::{ConstraintSections, Section, section},
+            hasher::{CredentialHasher, PedersenHasher, PoseidonHasher},
+            holder_key::HolderKey,
+            issuer_set::{ISSUER_SET_TREE_HEIGHT, IssuerPath, issuer_id_field},
+            merkle_tree::{CredentialPathVar, credential_root, membership_path, tree_capacity},
+            multi_credential::{ClaimShape, MultiCredentialCircuit},
+            nullifier::nullifier,
+            policy::{Policy, Submitted},
+            predicate::{Attribute, CmpOp, PredicateError},
+            revocation::{RevocationPath, non_revocation_path, revocation_root},
+            schema::SchemaError,
+            signature::{CredentialSignature, CredentialSignatureVar, IssuerSigningKey},
+        },
+        utils::utils::*,
+    };
     use ark_crypto_primitives::{
         crh::sha256::{
             Sha256,
@@ -39,9 +348,18 @@ mod test {
         },
         snark::CircuitSpecificSetupSNARK,
     };
-    use ark_ff::{One, Zero};
-    use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget};
-    use ark_relations::r1cs::ConstraintSynthesizer;
+    use ark_ec::AffineRepr;
+    use ark_ed_on_bn254::{EdwardsAffine, Fr as EdFr, constraints::EdwardsVar};
+    use ark_ff::{BigInteger, One, PrimeField, Zero};
+    use ark_r1cs_std::{
+        alloc::{AllocVar, AllocationMode},
+        eq::EqGadget,
+        fields::fp::FpVar,
+        prelude::Boolean,
+    };
+    use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+    use std::borrow::Borrow;
+    use tracing_subscriber::layer::SubscriberExt;
 
     #[derive(Clone)]
     // SHA256 해시의 preimage를 증명하는 회로
@@ -66,7 +384,7 @@ mod test {
             self,
             cs: ark_relations::r1cs::ConstraintSystemRef<F>,
         ) -> ark_relations::r1cs::Result<()> {
-            let input_var = to_byte_vars(cs.clone(), &self.input);
+            let input_var = to_byte_vars(cs.clone(), &self.input)?;
             // witness로 할당
             let expected_hash_var =
                 DigestVar::<F>::new_witness(cs.clone(), || Ok(self.expected_hash.to_vec()))?;
@@ -86,7 +404,7 @@ mod test {
             self,
             cs: ark_relations::r1cs::ConstraintSystemRef<F>,
         ) -> ark_relations::r1cs::Result<()> {
-            let input_var = to_byte_vars(cs.clone(), &self.input);
+            let input_var = to_byte_vars(cs.clone(), &self.input)?;
             // public input으로 할당
             let expected_hash_var =
                 DigestVar::<F>::new_input(cs.clone(), || Ok(self.expected_hash.to_vec()))?;
@@ -106,7 +424,7 @@ mod test {
     fn test_sha256_preimage_witness_only() {
         let input: [u8; 32] = [1u8; 32];
         let mut hasher = Sha256::new();
-        hasher.update(&input);
+        hasher.update(input);
         let expected_hash = hasher.finalize();
         let circuit = TestCircuitWitnessOnly {
             input,
@@ -138,7 +456,7 @@ mod test {
     fn test_sha256_preimage_public_input() {
         let input: [u8; 32] = [1u8; 32];
         let mut hasher = Sha256::new();
-        hasher.update(&input);
+        hasher.update(input);
         let expected_hash = hasher.finalize();
         let circuit = TestCircuitPublicInput {
             input,
@@ -160,7 +478,7 @@ mod test {
             .unwrap();
 
         let mut public_inputs = Vec::with_capacity(256);
-        let expected_hash_bytes: &[u8] = &expected_hash.to_vec();
+        let expected_hash_bytes: &[u8] = &expected_hash;
         for byte in expected_hash_bytes.iter() {
             for i in 0..8 {
                 // Little-endian
@@ -177,18 +495,106 @@ mod test {
         assert!(result);
     }
 
+    // 테스트에서는 setup/prove 시간을 줄이기 위해 높이가 낮은 credential tree 사용 (최대 4개)
+    const TEST_TREE_HEIGHT: usize = 3;
+
+    // 테스트에서 나이를 계산하는 기준 날짜
+    fn test_today() -> Date {
+        Date::new(2025, 6, 15).unwrap()
+    }
+
+    // 테스트 credential을 발급받는 Holder의 키
+    fn test_holder_key() -> HolderKey {
+        HolderKey::new(F::from(42u64))
+    }
+
+    // 테스트 credential의 유효 기간. `test_today`를 포함
+    fn test_validity() -> Validity {
+        Validity::new(
+            Date::new(2025, 1, 1).unwrap(),
+            Date::new(2029, 12, 31).unwrap(),
+        )
+        .unwrap()
+    }
+
+    // 테스트에서 증명을 받는 Verifier의 scope
+    fn test_scope() -> F {
+        Verifier::new("2").unwrap().scope()
+    }
+
+    // 테스트에서 사용하는 Verifier의 challenge
+    fn test_challenge() -> F {
+        F::from(20240615u64)
+    }
+
+    // 테스트 Holder가 `test_today`에 만 MIN_AGE세 이상임을 test Verifier에게 증명하는 circuit
+    // 폐기된 credential은 없음. 테스트에서 다루는 필드만 struct update로 바꿔서 사용
+    fn test_age_circuit<H: CredentialHasher>(
+        credential: Credential,
+        issuance: Issuance<H>,
+        non_revocation_path: RevocationPath,
+    ) -> AgeCircuit<H> {
+        AgeCircuit {
+            today: test_today(),
+            predicate: Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+            disclosure: Disclosure::none(),
+            encoding: CredentialEncoding::Bytes,
+            issuance,
+            scope: test_scope(),
+            challenge: test_challenge(),
+            holder_secret_key: test_holder_key().secret_key(),
+            credential,
+            revocation_root: revocation_root(TEST_TREE_HEIGHT, &[]).unwrap(),
+            non_revocation_path,
+        }
+    }
+
+    // Poseidon Issuer "1"이 테스트 Holder에게 `dob`에 태어난 "Alice"의 credential을 발급
+    fn signed_person_credential(
+        dob: Date,
+    ) -> (Issuer<PoseidonHasher>, Credential, CredentialSignature) {
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let credential = person_credential(
+            issuer.id,
+            test_holder_key().public_key,
+            test_validity(),
+            "Alice".to_string(),
+            dob,
+            F::from(rand::random::<u128>()),
+        )
+        .unwrap();
+        let signature = issuer.issue_credential(&credential).unwrap();
+        (issuer, credential, signature)
+    }
+
+    // circuit의 constraint를 만들고 모두 만족되는지 확인
+    fn is_satisfied(circuit: impl ConstraintSynthesizer<F>) -> bool {
+        let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
     #[test]
     fn test_did_scenario() {
-        let mut issuer = Issuer::new("1");
+        let mut issuer =
+            Issuer::<Sha256Hasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
 
+        // holder마다 자신의 키로 credential을 발급받음
+        let holder_keys: Vec<HolderKey> = (0..NUM_CREDENTIALS)
+            .map(|i| HolderKey::new(F::from(100 + i as u64)))
+            .collect();
         let mut credentials = Vec::new();
-        for i in 0..MAX_CREDENTIALS {
-            let cred = Credential {
-                issuer_id: issuer.id.clone(),
-                holder_name: format!("{}", 2005 + i as u32),
-                holder_dob_year: format!("{}", 2005 + i as u32), // 2005, 2006, 2007, ...
-                randomness: rand::random::<u128>().to_string(),
-            };
+        for (i, holder_key) in holder_keys.iter().enumerate() {
+            let cred = person_credential(
+                issuer.id,
+                holder_key.public_key,
+                test_validity(),
+                HOLDER_NAMES[i].to_string(),
+                Date::new(2005 + i as u32, 6, 15).unwrap(), // 2005-06-15, 2006-06-15, ...
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap();
             credentials.push(cred);
         }
 
@@ -197,293 +603,2863 @@ mod test {
             issuer.issue_credential(cred).unwrap();
         }
 
-        // Issuer가 publish한 해시된 credentials
-        let hashed_creds: [Sha256Digest; MAX_CREDENTIALS] = issuer
-            .hashed_credentials()
-            .unwrap()
-            .try_into()
-            .expect("Wrong length");
-
-        let holder_2005 = Holder::new("2005", credentials[0].clone());
-        let holder_2007 = Holder::new("2007", credentials[2].clone());
-
-        let public_inputs = {
-            let mut public_inputs = Vec::new();
-
-            // circuit의 generate_constraints에서와 "동일한 순서"로 public input 제공
-            let cutoff_year = <<Bn254 as Pairing>::ScalarField>::from_str(CUTOFF_YEAR).unwrap();
-            public_inputs.push(cutoff_year);
-
-            // hashed_credentials의 각 credential의 바이트를 비트 단위로 field 원소로 변환하여 public input으로 사용
-            for cred in &hashed_creds {
-                let cred_bytes: &[u8] = cred;
-                for byte in cred_bytes.iter() {
-                    for i in 0..8 {
-                        // Little-endian
-                        if (byte >> i) & 1 == 1 {
-                            public_inputs.push(F::one());
-                        } else {
-                            public_inputs.push(F::zero());
-                        }
-                    }
-                }
-            }
-            public_inputs
+        // Issuer가 publish한 해시된 credentials와 이를 leaf로 하는 Merkle tree의 root
+        let hashed_creds = issuer.hashed_credentials();
+        let credential_root = issuer.credential_root().unwrap();
+        let revoked = issuer.revoked_indices();
+        let revocation_root = issuer.revocation_root().unwrap();
+
+        let holder_2005 = Holder::new(holder_keys[0].clone(), credentials[0].clone()).unwrap();
+        let holder_2007 = Holder::new(holder_keys[2].clone(), credentials[2].clone()).unwrap();
+
+        // Verifier가 정한 조건과 Issuer가 publish한 credential root
+        // public input은 `Verifier::verify`가 circuit의 generate_constraints와 "동일한 순서"로 만듦
+        let policy = |challenge| Policy {
+            today: test_today(),
+            schema: person_schema(),
+            predicate: Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+            disclosure: Disclosure::none(),
+            challenge,
+        };
+        // credential root는 128비트씩 묶은 2개의 field 원소로 public input이 됨
+        let issuance = PublicIssuance::<Sha256Hasher>::Published {
+            credential_root: credential_root.clone(),
+            root_encoding: DigestEncoding::Packed,
         };
 
-        let verifier = Verifier::new("2");
-        let (proving_key, verifying_key) = verifier.setup().unwrap();
+        let mut verifier = Verifier::new("2").unwrap();
+        let (proving_key, verifying_key) = verifier
+            .setup::<Sha256Hasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Published(DigestEncoding::Packed),
+                &person_schema(),
+                &Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+                &Disclosure::none(),
+                CredentialEncoding::Bytes,
+            )
+            .unwrap();
+
+        // 다른 holder는 같은 Verifier에게도 다른 nullifier를 제출
+        let scope = verifier.scope();
+        assert_ne!(holder_2005.nullifier(scope), holder_2007.nullifier(scope));
 
-        let is_valid_2005 = {
-            // holder가 2005년생인 circuit
+        // holder가 2005년생인 circuit으로 challenge에 대한 증명
+        let prove_2005 = |challenge: F| {
             let age_circuit_2005 = AgeCircuit {
-                dob_cutoff_year: CUTOFF_YEAR.to_string(),
-                hashed_credentials: hashed_creds.clone(),
-                credential: holder_2005.credentials.clone(),
+                scope,
+                challenge,
+                holder_secret_key: holder_2005.secret_key(),
+                revocation_root,
+                ..test_age_circuit::<Sha256Hasher>(
+                    holder_2005.credential.clone(),
+                    Issuance::Published {
+                        credential_root: credential_root.clone(),
+                        root_encoding: DigestEncoding::Packed,
+                        membership_path: holder_2005
+                            .membership_path::<Sha256Hasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                            .unwrap(),
+                    },
+                    holder_2005
+                        .non_revocation_path::<Sha256Hasher>(
+                            TEST_TREE_HEIGHT,
+                            &hashed_creds,
+                            &revoked,
+                        )
+                        .unwrap(),
+                )
             };
 
             // prove
-            let proof_2005 = Holder::prove(proving_key.clone(), age_circuit_2005).unwrap();
+            assert_eq!(Holder::diagnose(age_circuit_2005.clone()).unwrap(), None);
+            Holder::prove(proving_key.clone(), age_circuit_2005).unwrap()
+        };
+        // 공개한 값, challenge binding과 nullifier는 holder가 증명과 함께 제출
+        let submitted_2005 = |challenge: F| Submitted {
+            disclosed: holder_2005.disclose(&Disclosure::none()).unwrap(),
+            challenge_binding: holder_2005.challenge_binding(challenge),
+            nullifier: holder_2005.nullifier(scope),
+        };
+
+        let result_2005 = {
+            let challenge = verifier.new_challenge();
+            let proof_2005 = prove_2005(challenge);
+            verifier.verify(
+                &verifying_key,
+                &policy(challenge),
+                &issuance,
+                revocation_root,
+                &submitted_2005(challenge),
+                &proof_2005,
+            )
+        };
 
-            // verify
-            Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof_2005).unwrap()
+        // 같은 holder가 새 challenge로 다시 증명하면 nullifier가 같으므로 거부
+        let result_2005_again = {
+            let challenge = verifier.new_challenge();
+            let proof_2005 = prove_2005(challenge);
+            verifier.verify(
+                &verifying_key,
+                &policy(challenge),
+                &issuance,
+                revocation_root,
+                &submitted_2005(challenge),
+                &proof_2005,
+            )
         };
 
-        // 2007년생인 holder는 만 18세 미만이므로 검증 실패해야 함
-        let is_valid_2007 = {
+        // 2007-06-15생 holder는 기준 날짜에 만 18세이므로 검증 실패해야 함
+        let result_2007 = {
             // holder가 2007년생인 circuit
+            let challenge = verifier.new_challenge();
             let age_circuit_2007 = AgeCircuit {
-                dob_cutoff_year: CUTOFF_YEAR.to_string(),
-                hashed_credentials: hashed_creds.clone(),
-                credential: holder_2007.credentials.clone(),
+                scope,
+                challenge,
+                holder_secret_key: holder_2007.secret_key(),
+                revocation_root,
+                ..test_age_circuit::<Sha256Hasher>(
+                    holder_2007.credential.clone(),
+                    Issuance::Published {
+                        credential_root: credential_root.clone(),
+                        root_encoding: DigestEncoding::Packed,
+                        membership_path: holder_2007
+                            .membership_path::<Sha256Hasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                            .unwrap(),
+                    },
+                    holder_2007
+                        .non_revocation_path::<Sha256Hasher>(
+                            TEST_TREE_HEIGHT,
+                            &hashed_creds,
+                            &revoked,
+                        )
+                        .unwrap(),
+                )
             };
 
+            // 증명 전에 나이 조건식이 만족되지 않는 것을 확인할 수 있음
+            let unsatisfied = Holder::diagnose(age_circuit_2007.clone()).unwrap().unwrap();
+            assert_eq!(unsatisfied.path, vec![Section::Predicate]);
+            assert!(
+                unsatisfied
+                    .to_string()
+                    .starts_with("predicate check (constraint ")
+            );
+            // 행렬로 찾은 index는 ark-relations가 보고하는 index와 같음 (tracing을 사용하지 않을 때)
+            let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+            age_circuit_2007
+                .clone()
+                .generate_constraints(cs.clone())
+                .unwrap();
+            assert_eq!(
+                cs.which_is_unsatisfied().unwrap(),
+                Some(unsatisfied.index.to_string())
+            );
+            assert!(matches!(
+                Holder::prove_checked(proving_key.clone(), age_circuit_2007.clone()),
+                Err(Error::PredicateNotSatisfied)
+            ));
+
             // prove
             let proof_2007 = Holder::prove(proving_key.clone(), age_circuit_2007).unwrap();
 
-            // verify
-            Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof_2007).unwrap()
+            // verify. 공개한 값, challenge binding과 nullifier는 holder가 증명과 함께 제출
+            let submitted = Submitted {
+                disclosed: holder_2007.disclose(&Disclosure::none()).unwrap(),
+                challenge_binding: holder_2007.challenge_binding(challenge),
+                nullifier: holder_2007.nullifier(scope),
+            };
+            verifier.verify(
+                &verifying_key,
+                &policy(challenge),
+                &issuance,
+                revocation_root,
+                &submitted,
+                &proof_2007,
+            )
         };
 
         // constraint 개수 출력
         {
             let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
             let circuit = AgeCircuit {
-                dob_cutoff_year: CUTOFF_YEAR.to_string(),
-                hashed_credentials: hashed_creds,
-                credential: holder_2005.credentials.clone(),
+                holder_secret_key: holder_2005.secret_key(),
+                revocation_root,
+                ..test_age_circuit::<Sha256Hasher>(
+                    holder_2005.credential.clone(),
+                    Issuance::Published {
+                        credential_root,
+                        root_encoding: DigestEncoding::Packed,
+                        membership_path: holder_2005
+                            .membership_path::<Sha256Hasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                            .unwrap(),
+                    },
+                    holder_2005
+                        .non_revocation_path::<Sha256Hasher>(
+                            TEST_TREE_HEIGHT,
+                            &hashed_creds,
+                            &revoked,
+                        )
+                        .unwrap(),
+                )
             };
             circuit.clone().generate_constraints(cs.clone()).unwrap();
             println!("Number of constraints: {}", cs.num_constraints());
         }
 
         // 2005년생은 검증에 성공하고 2007년생은 실패해야 함
-        assert!(is_valid_2005 && !is_valid_2007);
+        assert!(result_2005.is_ok());
+        assert!(matches!(result_2005_again, Err(Error::NullifierReused)));
+        assert!(matches!(result_2007, Err(Error::InvalidProof)));
     }
-}
 
-async fn send_tx(
-    proof: Vec<String>,
-    public_inputs: Vec<String>,
-    vk: Vec<String>,
-    contract_address: Address,
-) -> Result<(), Box<dyn std::error::Error>> {
-    dotenv::dotenv().ok();
-    let rpc_url = std::env::var("RPC_URL").expect("RPC_URL must be set");
-    let private_key = std::env::var("PRIVATE_KEY").expect("PRIVATE_KEY must be set");
+    #[test]
+    // credential root의 public input 방식에 따른 public input 개수와 값 확인
+    fn test_credential_root_encoding() {
+        let mut issuer =
+            Issuer::<Sha256Hasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let cred = person_credential(
+            issuer.id,
+            test_holder_key().public_key,
+            test_validity(),
+            "Alice".to_string(),
+            Date::new(2000, 1, 1).unwrap(),
+            F::from(rand::random::<u128>()),
+        )
+        .unwrap();
+        issuer.issue_credential(&cred).unwrap();
 
-    let provider = Provider::<Http>::try_from(rpc_url)?.interval(Duration::from_millis(10u64));
-    let chain_id = provider.get_chainid().await?;
+        let credential_root = issuer.credential_root().unwrap();
+        let membership_path = Holder::new(test_holder_key(), cred.clone())
+            .unwrap()
+            .membership_path::<Sha256Hasher>(TEST_TREE_HEIGHT, &issuer.hashed_credentials())
+            .unwrap();
+        let revocation_root = issuer.revocation_root().unwrap();
+        let non_revocation_path = non_revocation_path(TEST_TREE_HEIGHT, &[], 0).unwrap();
 
-    let wallet = private_key
-        .parse::<LocalWallet>()?
-        .with_chain_id(chain_id.as_u64());
-    let client = SignerMiddleware::new(provider, wallet.clone());
-    let client = Arc::new(client);
+        for (encoding, num_root_inputs) in
+            [(DigestEncoding::Bits, 256), (DigestEncoding::Packed, 2)]
+        {
+            let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+            let circuit = AgeCircuit {
+                revocation_root,
+                ..test_age_circuit::<Sha256Hasher>(
+                    cred.clone(),
+                    Issuance::Published {
+                        credential_root: credential_root.clone(),
+                        root_encoding: encoding,
+                        membership_path: membership_path.clone(),
+                    },
+                    non_revocation_path.clone(),
+                )
+            };
+            circuit.generate_constraints(cs.clone()).unwrap();
+            assert!(cs.is_satisfied().unwrap());
+
+            // 1 (상수) + 2 (기준 날짜, 조건식의 만 나이 기준) + credential root
+            // + 5 (revocation root, scope, challenge, challenge binding, nullifier)
+            assert_eq!(cs.num_instance_variables(), 8 + num_root_inputs);
+
+            // Rust에서 만든 public input이 circuit에 할당된 값과 같아야 함
+            let mut expected = vec![F::one(), test_today().to_field(), F::from(MIN_AGE)];
+            expected.extend(digest_to_field_elements(&credential_root, encoding));
+            expected.extend([
+                revocation_root,
+                test_scope(),
+                test_challenge(),
+                challenge_binding(test_holder_key().secret_key(), &cred, test_challenge()),
+                nullifier(test_holder_key().secret_key(), test_scope()),
+            ]);
+            assert_eq!(cs.borrow().unwrap().instance_assignment, expected);
+        }
+    }
 
-    let contract = Groth16Verifier::new(contract_address, client.clone());
+    #[test]
+    // Poseidon commitment를 사용하는 credential로 setup / prove / verify
+    // native 해시로 만든 root와 circuit 안에서 계산한 root가 같아야 검증 성공
+    fn test_poseidon_commitment() {
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
 
-    let mut proof_uints: [U256; 8] = [U256::zero(); 8];
-    for i in 0..8 {
-        proof_uints[i] = U256::from_str_radix(&proof[i], 10)?;
+        let mut credentials = Vec::new();
+        for (i, holder_name) in HOLDER_NAMES.iter().enumerate() {
+            let cred = person_credential(
+                issuer.id,
+                test_holder_key().public_key,
+                test_validity(),
+                holder_name.to_string(),
+                Date::new(2005 + i as u32, 6, 15).unwrap(),
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap();
+            issuer.issue_credential(&cred).unwrap();
+            credentials.push(cred);
+        }
+
+        let hashed_creds = issuer.hashed_credentials();
+        let credential_root = issuer.credential_root().unwrap();
+        let revocation_root = issuer.revocation_root().unwrap();
+
+        let holder_2005 = Holder::new(test_holder_key(), credentials[0].clone()).unwrap();
+        let age_circuit_2005 = AgeCircuit {
+            revocation_root,
+            ..test_age_circuit::<PoseidonHasher>(
+                holder_2005.credential.clone(),
+                Issuance::Published {
+                    credential_root,
+                    root_encoding: DigestEncoding::Packed,
+                    membership_path: holder_2005
+                        .membership_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                        .unwrap(),
+                },
+                holder_2005
+                    .non_revocation_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds, &[])
+                    .unwrap(),
+            )
+        };
+
+        // SHA256을 사용할 때보다 constraint 수가 훨씬 적어야 함
+        let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+        age_circuit_2005
+            .clone()
+            .generate_constraints(cs.clone())
+            .unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        println!("Number of constraints (Poseidon): {}", cs.num_constraints());
+        // credential 해시, credential / revocation tree의 Merkle path, nullifier / challenge binding 해시, 유효 기간 비교를 모두 포함
+        assert!(cs.num_constraints() < 10_000);
+
+        let verifier = Verifier::new("2").unwrap();
+        let (proving_key, verifying_key) = verifier
+            .setup::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Published(DigestEncoding::Packed),
+                &person_schema(),
+                &Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+                &Disclosure::none(),
+                CredentialEncoding::Bytes,
+            )
+            .unwrap();
+        let proof = Holder::prove(proving_key, age_circuit_2005).unwrap();
+
+        // public input: 기준 날짜, 만 나이 기준, Poseidon root (field 원소 1개), revocation root, scope,
+        // challenge, challenge binding, nullifier
+        let mut public_inputs = vec![test_today().to_field(), F::from(MIN_AGE)];
+        public_inputs.extend(PoseidonHasher::root_to_field_elements(
+            &credential_root,
+            DigestEncoding::Packed,
+        ));
+        public_inputs.extend([
+            revocation_root,
+            test_scope(),
+            test_challenge(),
+            holder_2005.challenge_binding(test_challenge()),
+            holder_2005.nullifier(test_scope()),
+        ]);
+        assert_eq!(public_inputs.len(), 8);
+        assert!(Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof).unwrap());
     }
 
-    // SHA256의 각 해시 값의 비트를 field 원소로 변환하여 public input으로 제공
-    // 3* 256 + 1 (cutoff year) = 769
-    let mut inputs: [U256; 769] = [U256::zero(); 769];
-    for i in 0..769 {
-        inputs[i] = U256::from_str_radix(&public_inputs[i], 10)?;
+    #[test]
+    // Pedersen commitment를 사용하는 credential의 membership / 나이 검사
+    // credential root는 곡선 위의 점 (x, y) 두 개의 field 원소로 제공
+    fn test_pedersen_commitment() {
+        let mut issuer =
+            Issuer::<PedersenHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+
+        let mut credentials = Vec::new();
+        for (i, holder_name) in HOLDER_NAMES.iter().enumerate() {
+            let cred = person_credential(
+                issuer.id,
+                test_holder_key().public_key,
+                test_validity(),
+                holder_name.to_string(),
+                Date::new(2005 + i as u32, 6, 15).unwrap(),
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap();
+            issuer.issue_credential(&cred).unwrap();
+            credentials.push(cred);
+        }
+
+        let hashed_creds = issuer.hashed_credentials();
+        let credential_root = issuer.credential_root().unwrap();
+
+        let revocation_root = issuer.revocation_root().unwrap();
+
+        let is_satisfied = |credential: &Credential| {
+            let holder = Holder::new(test_holder_key(), credential.clone()).unwrap();
+            let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+            let circuit = AgeCircuit {
+                revocation_root,
+                ..test_age_circuit::<PedersenHasher>(
+                    credential.clone(),
+                    Issuance::Published {
+                        credential_root,
+                        root_encoding: DigestEncoding::Packed,
+                        membership_path: holder
+                            .membership_path::<PedersenHasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                            .unwrap(),
+                    },
+                    holder
+                        .non_revocation_path::<PedersenHasher>(TEST_TREE_HEIGHT, &hashed_creds, &[])
+                        .unwrap(),
+                )
+            };
+            circuit.generate_constraints(cs.clone()).unwrap();
+            println!("Number of constraints (Pedersen): {}", cs.num_constraints());
+
+            // Rust에서 만든 public input이 circuit에 할당된 값과 같아야 함
+            let mut expected = vec![F::one(), test_today().to_field(), F::from(MIN_AGE)];
+            expected.extend(PedersenHasher::root_to_field_elements(
+                &credential_root,
+                DigestEncoding::Packed,
+            ));
+            expected.extend([
+                revocation_root,
+                test_scope(),
+                test_challenge(),
+                holder.challenge_binding(test_challenge()),
+                holder.nullifier(test_scope()),
+            ]);
+            assert_eq!(cs.borrow().unwrap().instance_assignment, expected);
+            cs.is_satisfied().unwrap()
+        };
+
+        // 2005년생은 만족하고 2007년생은 만족하지 않아야 함
+        assert!(is_satisfied(&credentials[0]));
+        assert!(!is_satisfied(&credentials[2]));
     }
 
-    let vk_alpha1 = G1Point {
-        x: U256::from_str_radix(&vk[0], 10)?,
-        y: U256::from_str_radix(&vk[1], 10)?,
-    };
-    let vk_beta2 = G2Point {
-        x: [
-            U256::from_str_radix(&vk[2], 10)?,
-            U256::from_str_radix(&vk[3], 10)?,
-        ],
-        y: [
-            U256::from_str_radix(&vk[4], 10)?,
-            U256::from_str_radix(&vk[5], 10)?,
-        ],
-    };
-    let vk_gamma2 = G2Point {
-        x: [
-            U256::from_str_radix(&vk[6], 10)?,
-            U256::from_str_radix(&vk[7], 10)?,
-        ],
-        y: [
-            U256::from_str_radix(&vk[8], 10)?,
-            U256::from_str_radix(&vk[9], 10)?,
-        ],
-    };
-    let vk_delta2 = G2Point {
-        x: [
-            U256::from_str_radix(&vk[10], 10)?,
-            U256::from_str_radix(&vk[11], 10)?,
-        ],
-        y: [
-            U256::from_str_radix(&vk[12], 10)?,
-            U256::from_str_radix(&vk[13], 10)?,
-        ],
-    };
+    #[test]
+    // Issuer가 발급하지 않은 credential은 다른 credential의 Merkle path를 사용해도 membership 검사를 통과할 수 없음
+    fn test_credential_tree_membership() {
+        let mut issuer =
+            Issuer::<Sha256Hasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let issued = person_credential(
+            issuer.id,
+            test_holder_key().public_key,
+            test_validity(),
+            "Alice".to_string(),
+            Date::new(2000, 1, 1).unwrap(),
+            F::from(rand::random::<u128>()),
+        )
+        .unwrap();
+        issuer.issue_credential(&issued).unwrap();
 
-    let mut vk_public_input_unconverted = vec![];
-    for i in (14..vk.len()).step_by(2) {
-        vk_public_input_unconverted.push([
-            U256::from_str_radix(&vk[i], 10)?,
-            U256::from_str_radix(&vk[i + 1], 10)?,
+        let hashed_creds = issuer.hashed_credentials();
+        let credential_root = issuer.credential_root().unwrap();
+        let membership_path = Holder::new(test_holder_key(), issued.clone())
+            .unwrap()
+            .membership_path::<Sha256Hasher>(TEST_TREE_HEIGHT, &hashed_creds)
+            .unwrap();
+
+        // 발급한 credential의 path는 native 검증도 통과해야 함
+        assert!(
+            membership_path
+                .verify(&(), &(), &credential_root, Sha256Hasher::encode(&issued))
+                .unwrap()
+        );
+
+        let is_satisfied = |credential: Credential| {
+            is_satisfied(test_age_circuit::<Sha256Hasher>(
+                credential,
+                Issuance::Published {
+                    credential_root: credential_root.clone(),
+                    root_encoding: DigestEncoding::Packed,
+                    membership_path: membership_path.clone(),
+                },
+                non_revocation_path(TEST_TREE_HEIGHT, &[], 0).unwrap(),
+            ))
+        };
+
+        // randomness만 바꾼 위조 credential
+        let mut forged = issued.clone();
+        forged.randomness = F::from(rand::random::<u128>());
+
+        assert!(is_satisfied(issued));
+        assert!(!is_satisfied(forged));
+
+        // 발급 가능 개수(2^(TEST_TREE_HEIGHT - 1) = 4)를 넘으면 발급 실패
+        for i in 1..=4 {
+            let cred = person_credential(
+                issuer.id,
+                test_holder_key().public_key,
+                test_validity(),
+                "Alice".to_string(),
+                Date::new(2000, 1, 1).unwrap(),
+                F::from(i as u64),
+            )
+            .unwrap();
+            assert_eq!(issuer.issue_credential(&cred).is_ok(), i < 4);
+        }
+    }
+
+    #[test]
+    // Issuer가 폐기한 credential은 revocation tree의 non-membership을 증명할 수 없음
+    fn test_revocation() {
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+
+        let mut credentials = Vec::new();
+        for (i, holder_name) in HOLDER_NAMES.iter().enumerate() {
+            let cred = person_credential(
+                issuer.id,
+                test_holder_key().public_key,
+                test_validity(),
+                holder_name.to_string(),
+                Date::new(2000 + i as u32, 1, 1).unwrap(),
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap();
+            issuer.issue_credential(&cred).unwrap();
+            credentials.push(cred);
+        }
+        let hashed_creds = issuer.hashed_credentials();
+        let credential_root = issuer.credential_root().unwrap();
+        let root_before = issuer.revocation_root().unwrap();
+        let stale_path = non_revocation_path(TEST_TREE_HEIGHT, &[], 1).unwrap();
+
+        // 두 번째 credential 폐기. 폐기 후 revocation root가 바뀌어야 함
+        issuer.revoke(&credentials[1]).unwrap();
+        let revoked = issuer.revoked_indices();
+        let revocation_root = issuer.revocation_root().unwrap();
+        assert_eq!(revoked, vec![1]);
+        assert_ne!(root_before, revocation_root);
+
+        // 이미 폐기했거나 발급하지 않은 credential은 폐기할 수 없음
+        assert!(matches!(
+            issuer.revoke(&credentials[1]),
+            Err(Error::AlreadyRevoked)
+        ));
+        let unissued = person_credential(
+            issuer.id,
+            test_holder_key().public_key,
+            test_validity(),
+            "x".to_string(),
+            Date::UNIX_EPOCH,
+            F::zero(),
+        )
+        .unwrap();
+        assert!(matches!(
+            issuer.revoke(&unissued),
+            Err(Error::CredentialNotIssued)
+        ));
+
+        // 폐기된 credential의 holder는 non-revocation path를 만들 수 없음
+        let holders: Vec<Holder> = credentials
+            .iter()
+            .map(|cred| Holder::new(test_holder_key(), cred.clone()).unwrap())
+            .collect();
+        assert!(matches!(
+            holders[1].non_revocation_path::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                &hashed_creds,
+                &revoked
+            ),
+            Err(Error::CredentialRevoked)
+        ));
+
+        let is_satisfied = |holder: &Holder, path: RevocationPath| {
+            let circuit = AgeCircuit {
+                revocation_root,
+                ..test_age_circuit::<PoseidonHasher>(
+                    holder.credential.clone(),
+                    Issuance::Published {
+                        credential_root,
+                        root_encoding: DigestEncoding::Packed,
+                        membership_path: holder
+                            .membership_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                            .unwrap(),
+                    },
+                    path,
+                )
+            };
+            is_satisfied(circuit)
+        };
+
+        // 폐기되지 않은 credential은 그대로 증명 가능
+        for i in [0, 2] {
+            let path = holders[i]
+                .non_revocation_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds, &revoked)
+                .unwrap();
+            assert!(is_satisfied(&holders[i], path));
+        }
+
+        // 폐기 전의 path나 다른 credential 번호의 path로는 폐기된 credential을 증명할 수 없음
+        assert!(!is_satisfied(&holders[1], stale_path));
+        let other_path = non_revocation_path(TEST_TREE_HEIGHT, &revoked, 0).unwrap();
+        assert!(!is_satisfied(&holders[1], other_path));
+    }
+
+    #[test]
+    // 같은 credential로 같은 Verifier에게 다시 증명하면 같은 nullifier가 공개되어 거부됨
+    fn test_nullifier() {
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let other_key = HolderKey::new(F::from(7u64));
+        let mut credentials = Vec::new();
+        for (i, holder_key) in [test_holder_key(), other_key.clone()].iter().enumerate() {
+            let cred = person_credential(
+                issuer.id,
+                holder_key.public_key,
+                test_validity(),
+                "Alice".to_string(),
+                Date::new(2000, 1, 1).unwrap(),
+                F::from(i as u64),
+            )
+            .unwrap();
+            issuer.issue_credential(&cred).unwrap();
+            credentials.push(cred);
+        }
+        let hashed_creds = issuer.hashed_credentials();
+        let holder = Holder::new(test_holder_key(), credentials[0].clone()).unwrap();
+        let other_holder = Holder::new(other_key, credentials[1].clone()).unwrap();
+
+        // nullifier는 Holder와 scope에 대해 결정적이고, scope나 Holder가 다르면 달라짐
+        let mut verifier = Verifier::new("2").unwrap();
+        let other_verifier = Verifier::new("3").unwrap();
+        let nullifier_0 = holder.nullifier(verifier.scope());
+        let nullifier_1 = other_holder.nullifier(verifier.scope());
+        assert_eq!(nullifier_0, holder.nullifier(verifier.scope()));
+        assert_ne!(nullifier_0, holder.nullifier(other_verifier.scope()));
+        assert_ne!(nullifier_0, nullifier_1);
+
+        // 같은 nullifier는 한 번만 받음
+        verifier.accept_nullifier(nullifier_0).unwrap();
+        assert!(matches!(
+            verifier.accept_nullifier(nullifier_0),
+            Err(Error::NullifierReused)
+        ));
+        verifier.accept_nullifier(nullifier_1).unwrap();
+
+        // circuit이 공개하는 nullifier는 holder가 계산한 값과 같고, 다른 값으로 바꾸면 만족되지 않음
+        let circuit = AgeCircuit {
+            scope: verifier.scope(),
+            ..test_age_circuit::<PoseidonHasher>(
+                holder.credential.clone(),
+                Issuance::Published {
+                    credential_root: issuer.credential_root().unwrap(),
+                    root_encoding: DigestEncoding::Packed,
+                    membership_path: holder
+                        .membership_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                        .unwrap(),
+                },
+                holder
+                    .non_revocation_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds, &[])
+                    .unwrap(),
+            )
+        };
+        assert_eq!(circuit.nullifier(), nullifier_0);
+
+        let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(
+            cs.borrow().unwrap().instance_assignment.last(),
+            Some(&nullifier_0)
+        );
+
+        *cs.borrow_mut()
+            .unwrap()
+            .instance_assignment
+            .last_mut()
+            .unwrap() = nullifier_1;
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    // 한 증명 요청의 증명은 challenge가 다른 요청에 재사용할 수 없음
+    fn test_challenge_binding() {
+        let mut verifier = Verifier::new("2").unwrap();
+        let challenge_0 = verifier.new_challenge();
+        let challenge_1 = verifier.new_challenge();
+        assert_ne!(challenge_0, challenge_1);
+
+        // 발급한 challenge만 한 번씩 받음
+        assert!(matches!(
+            verifier.accept_challenge(F::from(1u64)),
+            Err(Error::UnknownChallenge)
+        ));
+        verifier.accept_challenge(challenge_0).unwrap();
+        assert!(matches!(
+            verifier.accept_challenge(challenge_0),
+            Err(Error::UnknownChallenge)
+        ));
+
+        let (issuer, cred, signature) = signed_person_credential(Date::new(2000, 1, 1).unwrap());
+        let holder = Holder::new(test_holder_key(), cred.clone()).unwrap();
+        assert_ne!(
+            holder.challenge_binding(challenge_0),
+            holder.challenge_binding(challenge_1)
+        );
+
+        // challenge binding은 Holder 비밀 키 없이 계산할 수 없고, publish된 credential 해시와도 다름
+        // Issuer / Verifier가 credential 해시로 증명한 Holder를 찾을 수 없음
+        let binding = holder.challenge_binding(challenge_1);
+        assert_ne!(
+            challenge_binding(F::from(7u64), &cred, challenge_1),
+            binding
+        );
+        let hashed_creds = issuer.hashed_credentials();
+        assert!(hashed_creds.contains(&PoseidonHasher::hash(&cred)));
+        assert!(!hashed_creds.contains(&binding));
+
+        let circuit = AgeCircuit {
+            scope: verifier.scope(),
+            challenge: challenge_1,
+            ..test_age_circuit::<PoseidonHasher>(
+                cred,
+                Issuance::Signed {
+                    issuer_public_key: issuer.public_key(),
+                    signature,
+                },
+                non_revocation_path(TEST_TREE_HEIGHT, &[], 0).unwrap(),
+            )
+        };
+        assert_eq!(
+            circuit.challenge_binding(),
+            holder.challenge_binding(challenge_1)
+        );
+
+        // challenge_1 요청에 만든 증명은 challenge_0 요청의 public input으로는 검증되지 않음
+        let (proving_key, verifying_key) = verifier
+            .setup::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Signed,
+                &person_schema(),
+                &Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+                &Disclosure::none(),
+                CredentialEncoding::Bytes,
+            )
+            .unwrap();
+        let public_inputs = |challenge: F| {
+            let mut public_inputs = vec![test_today().to_field(), F::from(MIN_AGE)];
+            public_inputs.extend(circuit.issuance.public_inputs());
+            public_inputs.extend([
+                circuit.revocation_root,
+                verifier.scope(),
+                challenge,
+                circuit.challenge_binding(),
+                circuit.nullifier(),
+            ]);
+            public_inputs
+        };
+        let proof = Holder::prove(proving_key, circuit.clone()).unwrap();
+        assert!(
+            Groth16::<Bn254>::verify(&verifying_key, &public_inputs(challenge_1), &proof).unwrap()
+        );
+        assert!(
+            !Groth16::<Bn254>::verify(&verifying_key, &public_inputs(challenge_0), &proof).unwrap()
+        );
+        verifier.accept_challenge(challenge_1).unwrap();
+    }
+
+    #[test]
+    // credential을 알아내도 credential에 들어 있는 공개 키의 비밀 키 없이는 증명할 수 없음
+    fn test_holder_key_binding() {
+        let (issuer, cred, _) = signed_person_credential(Date::new(2000, 1, 1).unwrap());
+        let hashed_creds = issuer.hashed_credentials();
+
+        // 다른 키로는 Holder를 만들 수 없음
+        let thief_key = HolderKey::generate(&mut ark_std::rand::thread_rng());
+        assert_ne!(thief_key.public_key, cred.holder_public_key);
+        assert!(matches!(
+            Holder::new(thief_key.clone(), cred.clone()),
+            Err(Error::HolderKeyMismatch)
+        ));
+
+        let holder = Holder::new(test_holder_key(), cred.clone()).unwrap();
+        let is_satisfied = |holder_secret_key: F| {
+            let circuit = AgeCircuit {
+                holder_secret_key,
+                ..test_age_circuit::<PoseidonHasher>(
+                    cred.clone(),
+                    Issuance::Published {
+                        credential_root: issuer.credential_root().unwrap(),
+                        root_encoding: DigestEncoding::Packed,
+                        membership_path: holder
+                            .membership_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                            .unwrap(),
+                    },
+                    holder
+                        .non_revocation_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds, &[])
+                        .unwrap(),
+                )
+            };
+            is_satisfied(circuit)
+        };
+
+        // credential, randomness, Merkle path를 모두 알아도 비밀 키가 다르면 만족되지 않음
+        assert!(is_satisfied(holder.secret_key()));
+        assert!(!is_satisfied(thief_key.secret_key()));
+    }
+
+    #[test]
+    fn test_issuer_signature() {
+        // 서명 검증은 generator가 prime order 부분군의 원소라고 가정
+        assert!(
+            EdwardsAffine::generator()
+                .mul_bigint(EdFr::MODULUS)
+                .is_zero()
+        );
+
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let creds: Vec<Credential> = (0..2)
+            .map(|i| {
+                person_credential(
+                    issuer.id,
+                    test_holder_key().public_key,
+                    test_validity(),
+                    HOLDER_NAMES[i as usize].to_string(),
+                    Date::new(2000 + i, 1, 1).unwrap(),
+                    F::from(rand::random::<u128>()),
+                )
+                .unwrap()
+            })
+            .collect();
+        let signatures: Vec<CredentialSignature> = creds
+            .iter()
+            .map(|cred| issuer.issue_credential(cred).unwrap())
+            .collect();
+        assert_eq!(signatures[1].serial, 1);
+
+        // native 검증: 다른 Issuer의 키, 바뀐 credential, 바뀐 번호로는 검증 실패
+        let issuer_public_key = issuer.public_key();
+        let other_key = IssuerSigningKey::generate(&mut ark_std::rand::thread_rng());
+        let tampered_serial = CredentialSignature {
+            serial: 0,
+            ..signatures[1].clone()
+        };
+        assert!(verify_signature(
+            &issuer_public_key,
+            &creds[1],
+            &signatures[1]
+        ));
+        assert!(!verify_signature(
+            &other_key.public_key,
+            &creds[1],
+            &signatures[1]
+        ));
+        assert!(!verify_signature(
+            &issuer_public_key,
+            &creds[0],
+            &signatures[1]
+        ));
+        assert!(!verify_signature(
+            &issuer_public_key,
+            &creds[1],
+            &tampered_serial
+        ));
+
+        // S + l도 같은 검증식을 만족하므로 S >= l인 서명은 받지 않음
+        // native: l 이상의 S는 역직렬화되지 않음
+        let mut json = serde_json::to_value(&signatures[1]).unwrap();
+        json["s"] = hex::encode(EdFr::MODULUS.to_bytes_le()).into();
+        assert!(serde_json::from_value::<CredentialSignature>(json).is_err());
+        // circuit: S + l이 S와 같은 비트 수에 들어가는 서명을 골라 S + l의 비트로 검증
+        let key = IssuerSigningKey::generate(&mut ark_std::rand::thread_rng());
+        let (signature, forged_s) = (0..)
+            .find_map(|serial| {
+                let signature = key.sign(&creds[0], serial);
+                let mut forged_s = signature.s.into_bigint();
+                forged_s.add_with_carry(&EdFr::MODULUS);
+                (forged_s.num_bits() <= EdFr::MODULUS_BIT_SIZE).then_some((signature, forged_s))
+            })
+            .unwrap();
+        let signature_is_satisfied = |s: Option<Vec<bool>>| {
+            let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+            let credential_var = CredentialVar::new_witness(cs.clone(), || Ok(&creds[0])).unwrap();
+            let public_key_var =
+                EdwardsVar::new_witness(cs.clone(), || Ok(key.public_key)).unwrap();
+            let mut signature_var =
+                CredentialSignatureVar::new_witness(cs.clone(), || Ok(&signature)).unwrap();
+            if let Some(s) = s {
+                signature_var.s = Vec::new_witness(cs.clone(), || Ok(s)).unwrap();
+            }
+            signature_var
+                .verify(cs.clone(), &public_key_var, &credential_var)
+                .unwrap()
+                .enforce_equal(&Boolean::TRUE)
+                .unwrap();
+            cs.is_satisfied().unwrap()
+        };
+        assert!(signature_is_satisfied(None));
+        let mut forged_bits = forged_s.to_bits_le();
+        forged_bits.truncate(EdFr::MODULUS_BIT_SIZE as usize);
+        assert!(!signature_is_satisfied(Some(forged_bits)));
+
+        let is_satisfied = |issuer_public_key: EdwardsAffine,
+                            credential: &Credential,
+                            signature: &CredentialSignature,
+                            revoked: &[usize],
+                            serial: usize| {
+            let circuit = AgeCircuit {
+                revocation_root: revocation_root(TEST_TREE_HEIGHT, revoked).unwrap(),
+                ..test_age_circuit::<PoseidonHasher>(
+                    credential.clone(),
+                    Issuance::Signed {
+                        issuer_public_key,
+                        signature: signature.clone(),
+                    },
+                    non_revocation_path(TEST_TREE_HEIGHT, revoked, serial).unwrap(),
+                )
+            };
+            is_satisfied(circuit)
+        };
+
+        assert!(is_satisfied(
+            issuer_public_key,
+            &creds[1],
+            &signatures[1],
+            &[],
+            1
+        ));
+        // 다른 Issuer 공개 키, 서명받지 않은 credential, 바뀐 번호
+        assert!(!is_satisfied(
+            other_key.public_key,
+            &creds[1],
+            &signatures[1],
+            &[],
+            1
+        ));
+        assert!(!is_satisfied(
+            issuer_public_key,
+            &creds[0],
+            &signatures[1],
+            &[],
+            1
+        ));
+        assert!(!is_satisfied(
+            issuer_public_key,
+            &creds[1],
+            &tampered_serial,
+            &[],
+            0
+        ));
+
+        // 서명된 번호가 폐기되면 다른 번호의 non-revocation path로는 만족되지 않음
+        issuer.revoke(&creds[1]).unwrap();
+        let revoked = issuer.revoked_indices();
+        assert_eq!(revoked, vec![1]);
+        assert!(!is_satisfied(
+            issuer_public_key,
+            &creds[1],
+            &signatures[1],
+            &revoked,
+            0
+        ));
+        assert!(is_satisfied(
+            issuer_public_key,
+            &creds[0],
+            &signatures[0],
+            &revoked,
+            0
+        ));
+
+        // setup한 circuit으로 증명하고 공개 키를 public input으로 검증
+        let (proving_key, verifying_key) = Verifier::new("2")
+            .unwrap()
+            .setup::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Signed,
+                &person_schema(),
+                &Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+                &Disclosure::none(),
+                CredentialEncoding::Bytes,
+            )
+            .unwrap();
+        let circuit = AgeCircuit {
+            revocation_root: issuer.revocation_root().unwrap(),
+            ..test_age_circuit::<PoseidonHasher>(
+                creds[0].clone(),
+                Issuance::Signed {
+                    issuer_public_key,
+                    signature: signatures[0].clone(),
+                },
+                non_revocation_path(TEST_TREE_HEIGHT, &revoked, 0).unwrap(),
+            )
+        };
+        let public_inputs = circuit.public_inputs().unwrap();
+        let proof = Holder::prove(proving_key, circuit).unwrap();
+        assert!(Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_trusted_issuers() {
+        let mut issuer_a =
+            Issuer::<PoseidonHasher>::new("A", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let mut issuer_b =
+            Issuer::<PoseidonHasher>::new("B", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let mut issuer_c =
+            Issuer::<PoseidonHasher>::new("C", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let issuer_set = IssuerSet::new(vec![
+            issuer_a.trusted_issuer().unwrap(),
+            issuer_b.trusted_issuer().unwrap(),
+        ])
+        .unwrap();
+
+        let credential = |issuer_id: [u8; 32]| {
+            person_credential(
+                issuer_id,
+                test_holder_key().public_key,
+                test_validity(),
+                "Alice".to_string(),
+                Date::new(2000, 1, 1).unwrap(),
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap()
+        };
+        let cred_a = credential(issuer_a.id);
+        let signature_a = issuer_a.issue_credential(&cred_a).unwrap();
+        let cred_c = credential(issuer_c.id);
+        let signature_c = issuer_c.issue_credential(&cred_c).unwrap();
+        // B가 A의 id로 만든 credential에 서명
+        let forged = credential(issuer_a.id);
+        let signature_forged = issuer_b.issue_credential(&forged).unwrap();
+
+        // set에 없는 Issuer의 path는 만들 수 없음
+        assert!(matches!(
+            issuer_set.membership_path(&issuer_c.id),
+            Err(Error::UntrustedIssuer)
+        ));
+        let (public_key_a, path_a) = issuer_set.membership_path(&issuer_a.id).unwrap();
+        let (public_key_b, path_b) = issuer_set.membership_path(&issuer_b.id).unwrap();
+        assert_eq!(public_key_a, issuer_a.public_key());
+
+        let issuance = |revealed_issuer: Option<[u8; 32]>,
+                        (issuer_public_key, issuer_path): (EdwardsAffine, IssuerPath),
+                        signature: &CredentialSignature| {
+            Issuance::<PoseidonHasher>::TrustedIssuers {
+                issuer_set_root: issuer_set.root().unwrap(),
+                revealed_issuer,
+                issuer_public_key,
+                issuer_path,
+                signature: signature.clone(),
+            }
+        };
+        let is_accepted = |issuance: Issuance<PoseidonHasher>, credential: &Credential| {
+            let circuit = test_age_circuit(
+                credential.clone(),
+                issuance,
+                non_revocation_path(TEST_TREE_HEIGHT, &[], 0).unwrap(),
+            );
+            is_satisfied(circuit)
+        };
+
+        // issuer_id를 공개하거나 숨기고 증명
+        let hidden = issuance(None, (public_key_a, path_a.clone()), &signature_a);
+        let revealed = issuance(
+            Some(issuer_a.id),
+            (public_key_a, path_a.clone()),
+            &signature_a,
+        );
+        assert_eq!(hidden.public_inputs(), vec![issuer_set.root().unwrap()]);
+        assert_eq!(
+            revealed.public_inputs(),
+            vec![issuer_set.root().unwrap(), issuer_id_field(&issuer_a.id)]
+        );
+        assert!(is_accepted(hidden, &cred_a));
+        assert!(is_accepted(revealed, &cred_a));
+
+        // 다른 Issuer의 id를 공개할 수 없음
+        assert!(!is_accepted(
+            issuance(
+                Some(issuer_b.id),
+                (public_key_a, path_a.clone()),
+                &signature_a
+            ),
+            &cred_a
+        ));
+        // set에 없는 Issuer의 서명은 다른 Issuer의 path로 증명할 수 없음
+        assert!(!is_accepted(
+            issuance(None, (issuer_c.public_key(), path_a.clone()), &signature_c),
+            &cred_c
+        ));
+        // set의 Issuer라도 다른 Issuer의 id로 발급한 credential은 증명할 수 없음
+        assert!(!is_accepted(
+            issuance(None, (public_key_b, path_b.clone()), &signature_forged),
+            &forged
+        ));
+        assert!(!is_accepted(
+            issuance(None, (public_key_a, path_a), &signature_forged),
+            &forged
+        ));
+
+        // B가 폐기한 credential은 폐기 리스트가 빈 A의 revocation root로 증명할 수 없음
+        let cred_b = credential(issuer_b.id);
+        let signature_b = issuer_b.issue_credential(&cred_b).unwrap();
+        let kept_b = credential(issuer_b.id);
+        let signature_kept_b = issuer_b.issue_credential(&kept_b).unwrap();
+        issuer_b.revoke(&cred_b).unwrap();
+        let current_set = IssuerSet::new(vec![
+            issuer_a.trusted_issuer().unwrap(),
+            issuer_b.trusted_issuer().unwrap(),
+        ])
+        .unwrap();
+        let (public_key_b, path_b) = current_set.membership_path(&issuer_b.id).unwrap();
+        let is_satisfied_with_root =
+            |issuer: &Issuer<PoseidonHasher>,
+             credential: &Credential,
+             signature: &CredentialSignature| {
+                let circuit = AgeCircuit {
+                    revocation_root: issuer.revocation_root().unwrap(),
+                    ..test_age_circuit(
+                        credential.clone(),
+                        Issuance::<PoseidonHasher>::TrustedIssuers {
+                            issuer_set_root: current_set.root().unwrap(),
+                            revealed_issuer: None,
+                            issuer_public_key: public_key_b,
+                            issuer_path: path_b.clone(),
+                            signature: signature.clone(),
+                        },
+                        non_revocation_path(
+                            TEST_TREE_HEIGHT,
+                            &issuer.revoked_indices(),
+                            signature.serial as usize,
+                        )
+                        .unwrap(),
+                    )
+                };
+                is_satisfied(circuit)
+            };
+        assert!(!is_satisfied_with_root(&issuer_a, &cred_b, &signature_b));
+        assert!(is_satisfied_with_root(
+            &issuer_b,
+            &kept_b,
+            &signature_kept_b
+        ));
+        // 폐기하지 않은 credential도 발급한 Issuer의 root가 아니면 증명할 수 없음
+        assert!(!is_satisfied_with_root(
+            &issuer_a,
+            &kept_b,
+            &signature_kept_b
+        ));
+
+        // tree에 들어가는 Issuer 수보다 많이 등록할 수 없음
+        let capacity = tree_capacity(ISSUER_SET_TREE_HEIGHT);
+        let too_many = vec![issuer_a.trusted_issuer().unwrap(); capacity + 1];
+        assert!(matches!(
+            IssuerSet::new(too_many),
+            Err(Error::IssuerSetFull { capacity: c }) if c == capacity
+        ));
+    }
+
+    // Poseidon credential tree에 credential 하나를 발급하고
+    // `today` 기준 credential이 `predicate`를 만족함을 증명하는 circuit이 만족되는지 확인
+    fn satisfies(credential: &Credential, today: Date, predicate: Predicate) -> bool {
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, credential.schema.clone())
+                .unwrap();
+        issuer.issue_credential(credential).unwrap();
+
+        let holder = Holder::new(test_holder_key(), credential.clone()).unwrap();
+        let hashed_creds = issuer.hashed_credentials();
+
+        let circuit = AgeCircuit {
+            today,
+            predicate,
+            ..test_age_circuit::<PoseidonHasher>(
+                credential.clone(),
+                Issuance::Published {
+                    credential_root: issuer.credential_root().unwrap(),
+                    root_encoding: DigestEncoding::Packed,
+                    membership_path: holder
+                        .membership_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                        .unwrap(),
+                },
+                holder
+                    .non_revocation_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds, &[])
+                    .unwrap(),
+            )
+        };
+        is_satisfied(circuit)
+    }
+
+    // 생년월일이 `holder_dob`인 credential이 `today` 기준 만 `min_age`세 이상인지 circuit으로 확인
+    fn is_old_enough(holder_dob: Date, today: Date, min_age: u32) -> bool {
+        let cred = person_credential(
+            string_to_bytes("1").unwrap(),
+            test_holder_key().public_key,
+            // 유효 기간은 어떤 기준 날짜든 포함하도록 넓게
+            Validity::new(
+                Date::new(1, 1, 1).unwrap(),
+                Date::new(9999, 12, 31).unwrap(),
+            )
+            .unwrap(),
+            "Alice".to_string(),
+            holder_dob,
+            F::from(rand::random::<u128>()),
+        )
+        .unwrap();
+        satisfies(&cred, today, Predicate::age_at_least(HOLDER_DOB, min_age))
+    }
+
+    #[test]
+    // 생년뿐 아니라 생일이 지났는지까지 확인하여 만 나이를 정확히 판단해야 함
+    fn test_exact_age() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let today = date("2025-06-15");
+
+        // 기준 날짜가 19번째 생일이면 만 19세
+        assert!(is_old_enough(date("2006-06-15"), today, MIN_AGE));
+        assert!(is_old_enough(date("2006-01-01"), today, MIN_AGE));
+        // 기준 연도에 생일이 아직 지나지 않았으면 만 18세
+        assert!(!is_old_enough(date("2006-06-16"), today, MIN_AGE));
+        assert!(!is_old_enough(date("2006-12-31"), today, MIN_AGE));
+
+        // 2월 29일생은 평년에는 3월 1일에 한 살 증가
+        let leap_day = date("2004-02-29");
+        assert!(!is_old_enough(leap_day, date("2023-02-28"), MIN_AGE));
+        assert!(is_old_enough(leap_day, date("2023-03-01"), MIN_AGE));
+        assert_eq!(leap_day.age_on(date("2023-02-28")), 18);
+        assert_eq!(leap_day.age_on(date("2023-03-01")), 19);
+
+        // 존재하지 않는 날짜는 만들 수 없음
+        assert_eq!(Date::new(2023, 2, 29), Err(DateError::InvalidDay));
+        assert_eq!(Date::new(2023, 13, 1), Err(DateError::InvalidMonth));
+        assert_eq!("2023/01/01".parse::<Date>(), Err(DateError::InvalidFormat));
+    }
+
+    #[test]
+    // 기준 날짜가 credential의 발급일 ~ 만료일 밖이면 증명할 수 없음
+    fn test_validity_period() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        assert_eq!(
+            Validity::new(date("2025-01-02"), date("2025-01-01")),
+            Err(DateError::ExpiresBeforeIssued)
+        );
+
+        let cred = person_credential(
+            string_to_bytes("1").unwrap(),
+            test_holder_key().public_key,
+            test_validity(),
+            "Alice".to_string(),
+            date("2000-01-01"),
+            F::from(rand::random::<u128>()),
+        )
+        .unwrap();
+        let predicate = || Predicate::age_at_least(HOLDER_DOB, MIN_AGE);
+
+        // 발급일과 만료일 당일은 유효
+        for (today, is_valid) in [
+            ("2024-12-31", false),
+            ("2025-01-01", true),
+            ("2029-12-31", true),
+            ("2030-01-01", false),
+        ] {
+            assert_eq!(test_validity().contains(date(today)), is_valid);
+            assert_eq!(satisfies(&cred, date(today), predicate()), is_valid);
+        }
+
+        // 유효 기간은 credential 해시에 포함되므로 Holder가 만료일을 늘릴 수 없음
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        issuer.issue_credential(&cred).unwrap();
+        let hashed_creds = issuer.hashed_credentials();
+        let holder = Holder::new(test_holder_key(), cred.clone()).unwrap();
+        let mut extended = cred.clone();
+        extended.validity = Validity::new(date("2025-01-01"), date("2039-12-31")).unwrap();
+        assert_ne!(PoseidonHasher::hash(&extended), PoseidonHasher::hash(&cred));
+
+        let circuit = AgeCircuit {
+            today: date("2030-01-01"),
+            predicate: predicate(),
+            ..test_age_circuit::<PoseidonHasher>(
+                extended,
+                Issuance::Published {
+                    credential_root: issuer.credential_root().unwrap(),
+                    root_encoding: DigestEncoding::Packed,
+                    membership_path: holder
+                        .membership_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds)
+                        .unwrap(),
+                },
+                holder
+                    .non_revocation_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &hashed_creds, &[])
+                    .unwrap(),
+            )
+        };
+        assert!(!is_satisfied(circuit));
+    }
+
+    #[test]
+    // Holder는 Verifier가 요청한 속성만 공개하고 나머지는 숨김
+    // Verifier는 공개된 값을 schema에 따라 다시 인코딩하여 public input으로 사용
+    fn test_selective_disclosure() {
+        let verifier = Verifier::new("2").unwrap();
+        let predicate = || Predicate::age_at_least(HOLDER_DOB, MIN_AGE);
+
+        // schema에 없거나 중복된 속성은 공개할 수 없음
+        for disclosure in [
+            Disclosure::none().with_attribute("holder_email"),
+            Disclosure::none()
+                .with_attribute(HOLDER_NAME)
+                .with_attribute(HOLDER_NAME),
+        ] {
+            assert!(matches!(
+                verifier.setup::<PoseidonHasher>(
+                    TEST_TREE_HEIGHT,
+                    IssuanceMode::Signed,
+                    &person_schema(),
+                    &predicate(),
+                    &disclosure,
+                    CredentialEncoding::Bytes,
+                ),
+                Err(Error::Schema(
+                    SchemaError::UnknownAttribute | SchemaError::DuplicateAttribute
+                ))
+            ));
+        }
+
+        let (issuer, cred, signature) = signed_person_credential(Date::new(2000, 1, 1).unwrap());
+        let holder = Holder::new(test_holder_key(), cred.clone()).unwrap();
+
+        // 이름만 공개. 생년월일은 공개하지 않음
+        let disclosure = Disclosure::none().with_attribute(HOLDER_NAME);
+        let disclosed = holder.disclose(&disclosure).unwrap();
+        assert_eq!(disclosed.issuer_id, None);
+        assert_eq!(
+            disclosed.attributes,
+            vec![(HOLDER_NAME.to_string(), Value::String("Alice".to_string()))]
+        );
+        let disclosed_inputs = verifier
+            .disclosed_inputs(&person_schema(), &disclosure, &disclosed)
+            .unwrap();
+        assert_eq!(
+            disclosed_inputs,
+            vec![cred.attribute_field(HOLDER_NAME).unwrap()]
+        );
+
+        // 요청과 다른 속성이나 schema와 다른 타입의 값은 거부
+        let mut other_attribute = disclosed.clone();
+        other_attribute.attributes[0].0 = HOLDER_DOB.to_string();
+        let mut wrong_type = disclosed.clone();
+        wrong_type.attributes[0].1 = Value::Integer(1);
+        let mut extra_issuer = disclosed.clone();
+        extra_issuer.issuer_id = Some(issuer.id);
+        for (tampered, expected) in [
+            (other_attribute, Error::DisclosureMismatch),
+            (wrong_type, Error::Schema(SchemaError::TypeMismatch)),
+            (extra_issuer, Error::DisclosureMismatch),
+        ] {
+            assert_eq!(
+                verifier
+                    .disclosed_inputs(&person_schema(), &disclosure, &tampered)
+                    .unwrap_err()
+                    .to_string(),
+                expected.to_string()
+            );
+        }
+
+        let circuit = |disclosure: Disclosure| AgeCircuit {
+            predicate: predicate(),
+            disclosure,
+            scope: verifier.scope(),
+            ..test_age_circuit::<PoseidonHasher>(
+                cred.clone(),
+                Issuance::Signed {
+                    issuer_public_key: issuer.public_key(),
+                    signature: signature.clone(),
+                },
+                non_revocation_path(TEST_TREE_HEIGHT, &[], 0).unwrap(),
+            )
+        };
+
+        // 공개한 값마다 public input이 하나씩 늘어남
+        let num_instances = |disclosure: Disclosure| {
+            let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+            circuit(disclosure)
+                .generate_constraints(cs.clone())
+                .unwrap();
+            assert!(cs.is_satisfied().unwrap());
+            cs.num_instance_variables()
+        };
+        assert_eq!(
+            num_instances(
+                Disclosure::none()
+                    .with_issuer_id()
+                    .with_attribute(HOLDER_DOB)
+                    .with_attribute(HOLDER_NAME)
+            ),
+            num_instances(Disclosure::none()) + 3
+        );
+
+        // 증명은 Holder가 공개한 값으로만 검증됨
+        let (proving_key, verifying_key) = verifier
+            .setup::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Signed,
+                &person_schema(),
+                &predicate(),
+                &disclosure,
+                CredentialEncoding::Bytes,
+            )
+            .unwrap();
+        let circuit = circuit(disclosure.clone());
+        let public_inputs = |disclosed_inputs: Vec<F>| {
+            let mut public_inputs = vec![test_today().to_field(), F::from(MIN_AGE)];
+            public_inputs.extend(disclosed_inputs);
+            public_inputs.extend(circuit.issuance.public_inputs());
+            public_inputs.extend([
+                circuit.revocation_root,
+                verifier.scope(),
+                circuit.challenge,
+                circuit.challenge_binding(),
+                circuit.nullifier(),
+            ]);
+            public_inputs
+        };
+        let proof = Holder::prove(proving_key, circuit.clone()).unwrap();
+        assert!(
+            Groth16::<Bn254>::verify(&verifying_key, &public_inputs(disclosed_inputs), &proof)
+                .unwrap()
+        );
+
+        // 다른 이름을 공개 값으로 제출하면 검증 실패
+        let mut forged = disclosed.clone();
+        forged.attributes[0].1 = Value::String("Bob".to_string());
+        let forged_inputs = verifier
+            .disclosed_inputs(&person_schema(), &disclosure, &forged)
+            .unwrap();
+        assert!(
+            !Groth16::<Bn254>::verify(&verifying_key, &public_inputs(forged_inputs), &proof)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    // 정부 신분증 credential의 나이와 동호회 credential의 회원 등급을 한 증명으로 증명
+    // 두 credential은 서로 다른 Issuer가 발급했지만 같은 Holder의 것이어야 함
+    fn test_multi_credential() {
+        let verifier = Verifier::new("2").unwrap();
+        let (government, id_credential, id_signature) =
+            signed_person_credential(Date::new(2000, 1, 1).unwrap());
+        let mut club =
+            Issuer::<PoseidonHasher>::new("3", TEST_TREE_HEIGHT, membership_schema()).unwrap();
+
+        let gold = membership_credential(1200, "gold");
+        let gold_signature = club.issue_credential(&gold).unwrap();
+        let silver = membership_credential(300, "silver");
+        let silver_signature = club.issue_credential(&silver).unwrap();
+
+        let age_predicate = || Predicate::age_at_least(HOLDER_DOB, MIN_AGE);
+        let level_predicate = || {
+            Predicate::compare(
+                Attribute::named("level"),
+                CmpOp::Eq,
+                Value::Enum("gold".to_string()),
+            )
+        };
+        let claim = |issuer: &Issuer<PoseidonHasher>,
+                     credential: &Credential,
+                     signature: &CredentialSignature,
+                     predicate: Predicate| CredentialClaim::<PoseidonHasher> {
+            predicate,
+            disclosure: Disclosure::none(),
+            encoding: CredentialEncoding::Bytes,
+            issuance: Issuance::Signed {
+                issuer_public_key: issuer.public_key(),
+                signature: signature.clone(),
+            },
+            revocation_root: issuer.revocation_root().unwrap(),
+            credential: credential.clone(),
+            non_revocation_path: non_revocation_path(
+                TEST_TREE_HEIGHT,
+                &[],
+                signature.serial as usize,
+            )
+            .unwrap(),
+        };
+        let id_claim = || claim(&government, &id_credential, &id_signature, age_predicate());
+
+        let holder = Holder::new(test_holder_key(), id_credential.clone()).unwrap();
+        let multi_circuit = |claims| {
+            holder
+                .multi_credential_circuit(test_today(), claims, verifier.scope(), test_challenge())
+                .unwrap()
+        };
+        // 두 credential의 조건식을 모두 만족해야 함
+        let circuit = multi_circuit(vec![
+            id_claim(),
+            claim(&club, &gold, &gold_signature, level_predicate()),
+        ]);
+        assert!(is_satisfied(circuit.clone()));
+        let silver_circuit = multi_circuit(vec![
+            id_claim(),
+            claim(&club, &silver, &silver_signature, level_predicate()),
         ]);
+        assert!(!is_satisfied(silver_circuit.clone()));
+        // 만족되지 않는 constraint는 두 번째 credential의 조건식
+        let unsatisfied = Holder::diagnose(silver_circuit).unwrap().unwrap();
+        assert_eq!(
+            unsatisfied.path,
+            vec![Section::Claim(1), Section::Predicate]
+        );
+        assert!(
+            unsatisfied
+                .to_string()
+                .starts_with("claim 1 / predicate check")
+        );
+
+        // 다른 Holder의 credential은 함께 사용할 수 없음
+        let other_key = HolderKey::new(F::from(7u64));
+        let mut others = gold.clone();
+        others.holder_public_key = other_key.public_key;
+        let others_signature = club.issue_credential(&others).unwrap();
+        let mixed_claims = vec![
+            id_claim(),
+            claim(&club, &others, &others_signature, level_predicate()),
+        ];
+        assert!(matches!(
+            holder.multi_credential_circuit(
+                test_today(),
+                mixed_claims.clone(),
+                verifier.scope(),
+                test_challenge()
+            ),
+            Err(Error::HolderKeyMismatch)
+        ));
+        for holder_secret_key in [test_holder_key().secret_key(), other_key.secret_key()] {
+            let mixed_circuit = MultiCredentialCircuit {
+                today: test_today(),
+                claims: mixed_claims.clone(),
+                scope: verifier.scope(),
+                challenge: test_challenge(),
+                holder_secret_key,
+            };
+            assert!(!is_satisfied(mixed_circuit.clone()));
+            let unsatisfied = Holder::diagnose(mixed_circuit).unwrap().unwrap();
+            assert_eq!(unsatisfied.path, vec![Section::HolderKey]);
+        }
+
+        // credential이 없으면 증명할 수 없음
+        assert!(matches!(
+            holder.multi_credential_circuit::<PoseidonHasher>(
+                test_today(),
+                vec![],
+                verifier.scope(),
+                test_challenge()
+            ),
+            Err(Error::NoCredentials)
+        ));
+        assert!(matches!(
+            verifier.setup_multi::<PoseidonHasher>(TEST_TREE_HEIGHT, &[]),
+            Err(Error::NoCredentials)
+        ));
+
+        // Groth16 증명 하나로 두 credential을 검증
+        let (proving_key, verifying_key) = verifier
+            .setup_multi::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                &[
+                    ClaimShape {
+                        mode: IssuanceMode::Signed,
+                        schema: person_schema(),
+                        predicate: age_predicate(),
+                        disclosure: Disclosure::none(),
+                        encoding: CredentialEncoding::Bytes,
+                    },
+                    ClaimShape {
+                        mode: IssuanceMode::Signed,
+                        schema: membership_schema(),
+                        predicate: level_predicate(),
+                        disclosure: Disclosure::none(),
+                        encoding: CredentialEncoding::Bytes,
+                    },
+                ],
+            )
+            .unwrap();
+        let mut public_inputs = vec![test_today().to_field()];
+        for claim in &circuit.claims {
+            public_inputs.extend(
+                claim
+                    .predicate
+                    .public_inputs(&claim.credential.schema)
+                    .unwrap(),
+            );
+            public_inputs.extend(claim.issuance.public_inputs());
+            public_inputs.push(claim.revocation_root);
+        }
+        public_inputs.extend([verifier.scope(), test_challenge()]);
+        public_inputs.extend(circuit.challenge_bindings());
+        public_inputs.push(circuit.nullifier());
+        // nullifier는 credential이 아니라 Holder와 scope로 결정
+        assert_eq!(circuit.nullifier(), holder.nullifier(verifier.scope()));
+
+        let proof = Holder::prove_multi(proving_key, circuit).unwrap();
+        assert!(Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof).unwrap());
     }
-    let vk_public_input: Vec<G1Point> = vk_public_input_unconverted
-        .into_iter()
-        .map(|p| G1Point { x: p[0], y: p[1] })
-        .collect();
 
-    let vk_public_input: [G1Point; 770] = vk_public_input
-        .try_into()
-        .expect("vk_public_input_vec should have 770 elements");
+    #[test]
+    // Canonical 할당에서는 `string_to_bytes`로 만들 수 없는 issuer_id를 circuit에 넣을 수 없음
+    // (Bytes 할당에서는 해시만 맞으면 어떤 바이트든 할당 가능)
+    fn test_canonical_encoding() {
+        let is_encodable = |credential: &Credential, encoding| {
+            let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+            CredentialVar::new_encoded(
+                cs.clone(),
+                credential.clone(),
+                AllocationMode::Witness,
+                encoding,
+            )
+            .unwrap();
+            cs.is_satisfied().unwrap()
+        };
 
-    let verifying_key = VerifyingKey {
-        alpha_1: vk_alpha1,
-        beta_2: vk_beta2,
-        gamma_2: vk_gamma2,
-        delta_2: vk_delta2,
-        public_input: vk_public_input,
-    };
+        let (issuer, cred, signature) = signed_person_credential(Date::new(2000, 1, 1).unwrap());
+        assert!(is_encodable(&cred, CredentialEncoding::Bytes));
+        assert!(is_encodable(&cred, CredentialEncoding::Canonical));
+        assert!(is_encodable(
+            &membership_credential(u64::MAX, "gold"),
+            CredentialEncoding::Canonical
+        ));
+
+        // 길이 이후의 바이트가 0이 아니거나, 길이가 MAX_STRING_BYTES보다 긴 issuer_id
+        let mut nonzero_padding = string_to_bytes("1").unwrap();
+        nonzero_padding[5] = 7;
+        let mut too_long = string_to_bytes("1").unwrap();
+        too_long[MAX_STRING_BYTES] = MAX_STRING_BYTES as u8 + 1;
+        for issuer_id in [nonzero_padding, too_long] {
+            let mut malformed = cred.clone();
+            malformed.issuer_id = issuer_id;
+            assert!(is_encodable(&malformed, CredentialEncoding::Bytes));
+            assert!(!is_encodable(&malformed, CredentialEncoding::Canonical));
+        }
 
-    let tx = contract.verify_proof(proof_uints, inputs, verifying_key);
-    let pending_tx = tx.send().await?;
-    let receipt = pending_tx.await?.unwrap();
+        // Canonical 할당으로 만든 키로 증명하고 검증
+        let verifier = Verifier::new("2").unwrap();
+        let circuit = AgeCircuit {
+            encoding: CredentialEncoding::Canonical,
+            scope: verifier.scope(),
+            ..test_age_circuit::<PoseidonHasher>(
+                cred,
+                Issuance::Signed {
+                    issuer_public_key: issuer.public_key(),
+                    signature,
+                },
+                non_revocation_path(TEST_TREE_HEIGHT, &[], 0).unwrap(),
+            )
+        };
+        let (proving_key, verifying_key) = verifier
+            .setup::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Signed,
+                &person_schema(),
+                &circuit.predicate,
+                &circuit.disclosure,
+                CredentialEncoding::Canonical,
+            )
+            .unwrap();
+        let mut public_inputs = vec![test_today().to_field(), F::from(MIN_AGE)];
+        public_inputs.extend(circuit.issuance.public_inputs());
+        public_inputs.extend([
+            circuit.revocation_root,
+            verifier.scope(),
+            circuit.challenge,
+            circuit.challenge_binding(),
+            circuit.nullifier(),
+        ]);
+        let proof = Holder::prove(proving_key, circuit).unwrap();
+        assert!(Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof).unwrap());
+    }
 
-    println!("Transaction receipt: {:?}", receipt);
+    #[test]
+    // 증명 전 확인: 만족되지 않는 circuit은 증명을 만들기 전에 원인별 오류로 거부됨
+    fn test_preflight() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let (mut issuer, adult, adult_signature) = signed_person_credential(date("2000-01-01"));
+        let young = person_credential(
+            issuer.id,
+            test_holder_key().public_key,
+            test_validity(),
+            "김민수".to_string(),
+            date("2007-06-15"),
+            F::from(rand::random::<u128>()),
+        )
+        .unwrap();
+        let young_signature = issuer.issue_credential(&young).unwrap();
+        let creds = [adult, young];
+        let signatures = [adult_signature, young_signature];
+        let stale_path = non_revocation_path(TEST_TREE_HEIGHT, &[], 0).unwrap();
+
+        let circuit = |index: usize, signature: &CredentialSignature, today: Date| AgeCircuit {
+            today,
+            revocation_root: issuer.revocation_root().unwrap(),
+            ..test_age_circuit::<PoseidonHasher>(
+                creds[index].clone(),
+                Issuance::Signed {
+                    issuer_public_key: issuer.public_key(),
+                    signature: signature.clone(),
+                },
+                non_revocation_path(TEST_TREE_HEIGHT, &issuer.revoked_indices(), index).unwrap(),
+            )
+        };
 
-    let tx = contract.get_pairing_result();
-    let pairing_result: bool = tx.call().await?;
-    println!("Pairing result from contract: {}", pairing_result);
+        // 만족하는 circuit은 그대로 증명
+        let valid = circuit(0, &signatures[0], test_today());
+        assert!(Holder::preflight(valid.clone()).is_ok());
+
+        // 나이 미달, 만료, 서명받지 않은 credential, 다른 Holder 비밀 키
+        assert!(matches!(
+            Holder::preflight(circuit(1, &signatures[1], test_today())),
+            Err(Error::PredicateNotSatisfied)
+        ));
+        assert!(matches!(
+            Holder::preflight(circuit(0, &signatures[0], date("2030-01-01"))),
+            Err(Error::CredentialExpired)
+        ));
+        assert!(matches!(
+            Holder::preflight(circuit(0, &signatures[1], test_today())),
+            Err(Error::InvalidSignature)
+        ));
+        let other_holder = AgeCircuit {
+            holder_secret_key: F::from(7u64),
+            ..valid.clone()
+        };
+        assert!(matches!(
+            Holder::preflight(other_holder),
+            Err(Error::HolderKeyMismatch)
+        ));
+
+        // `ConstraintLayer`로 constraint trace를 켜도 같은 원인별 오류
+        let subscriber = tracing_subscriber::Registry::default()
+            .with(ark_relations::r1cs::ConstraintLayer::default());
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(matches!(
+                Holder::preflight(circuit(1, &signatures[1], test_today())),
+                Err(Error::PredicateNotSatisfied)
+            ));
+            assert!(Holder::preflight(valid.clone()).is_ok());
+        });
+
+        // 공개한 credential 리스트에 없는 credential
+        let mut published =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        published.issue_credential(&creds[1]).unwrap();
+        let hashed_creds = published.hashed_credentials();
+        let not_published = AgeCircuit {
+            issuance: Issuance::Published {
+                credential_root: published.credential_root().unwrap(),
+                root_encoding: DigestEncoding::Packed,
+                membership_path: membership_path::<PoseidonHasher>(
+                    TEST_TREE_HEIGHT,
+                    &hashed_creds,
+                    0,
+                )
+                .unwrap(),
+            },
+            ..valid.clone()
+        };
+        assert!(matches!(
+            Holder::preflight(not_published),
+            Err(Error::NotAMember)
+        ));
+
+        // 폐기된 credential은 폐기 전의 path로도 증명할 수 없음
+        issuer.revoke(&creds[0]).unwrap();
+        let revoked = AgeCircuit {
+            revocation_root: issuer.revocation_root().unwrap(),
+            non_revocation_path: stale_path,
+            ..valid.clone()
+        };
+        assert!(matches!(
+            Holder::preflight(revoked.clone()),
+            Err(Error::CredentialRevoked)
+        ));
 
-    Ok(())
-}
+        // `prove_checked`는 만족되지 않는 circuit으로 증명을 만들지 않음
+        let (proving_key, verifying_key) = Verifier::new("2")
+            .unwrap()
+            .setup::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Signed,
+                &person_schema(),
+                &Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+                &Disclosure::none(),
+                CredentialEncoding::Bytes,
+            )
+            .unwrap();
+        assert!(matches!(
+            Holder::prove_checked(proving_key.clone(), revoked),
+            Err(Error::CredentialRevoked)
+        ));
 
-#[tokio::main]
-async fn main() {
-    let mut issuer = Issuer::new("1");
+        let public_inputs = valid.public_inputs().unwrap();
+        let proof = Holder::prove_checked(proving_key, valid).unwrap();
+        assert!(Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof).unwrap());
 
-    // ------------------------------ Issuer ------------------------------
-    // credential 준비
-    let mut credentials = Vec::new();
-    for i in 0..MAX_CREDENTIALS {
-        let cred = Credential {
-            issuer_id: issuer.id.clone(),
-            holder_name: format!("{}", 2005 + i as u32),
-            holder_dob_year: format!("{}", 2005 + i as u32), // 2005, 2006, 2007, ...
-            randomness: rand::random::<u128>().to_string(),
+        // 구간 안에서 `?`로 오류를 반환해도 구간이 닫혀 다음 구간이 그 안에 기록되지 않음
+        use ::core; // `ns!`의 `core::mem`이 ethers prelude의 `core`를 가리키지 않도록
+        let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+        let mut sections = ConstraintSections::new();
+        let failed = section!(sections, cs, Section::Signature, {
+            let missing = FpVar::new_witness(cs.clone(), || {
+                Err::<F, _>(SynthesisError::AssignmentMissing)
+            })?;
+            missing.enforce_equal(&FpVar::Constant(F::one()))
+        });
+        assert!(matches!(failed, Err(SynthesisError::AssignmentMissing)));
+        section!(sections, cs, Section::Validity, {
+            FpVar::new_witness(cs.clone(), || Ok(F::one()))?
+                .enforce_equal(&FpVar::Constant(F::one()))
+        })
+        .unwrap();
+        assert_eq!(sections.path_of(0), Some(vec![Section::Validity]));
+    }
+
+    #[test]
+    // 여러 Issuer의 credential을 wallet에 보관하고 파일로 저장 / 복원
+    // 증명 요청을 만족하는 credential을 찾아 circuit을 만듦
+    fn test_wallet() {
+        let mut government =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let mut club =
+            Issuer::<PoseidonHasher>::new("3", TEST_TREE_HEIGHT, membership_schema()).unwrap();
+        let mut untrusted =
+            Issuer::<PoseidonHasher>::new("4", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let person = |issuer: &Issuer<PoseidonHasher>, name: &str, dob: &str| {
+            person_credential(
+                issuer.id,
+                test_holder_key().public_key,
+                test_validity(),
+                name.to_string(),
+                dob.parse().unwrap(),
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap()
         };
-        credentials.push(cred);
+
+        let young = person(&government, "김민수", "2007-06-15");
+        let unknown_issuer = person(&untrusted, "Alice", "2000-01-01");
+        let revoked = person(&government, "Bob", "2001-01-01");
+        let adult = person(&government, "José Álvarez", "2002-01-01");
+        let mut gold = membership_credential(1200, "gold");
+        gold.issuer_id = club.id;
+
+        let mut wallet = Wallet::new(test_holder_key());
+        let signatures = [
+            government.issue_credential(&young).unwrap(),
+            untrusted.issue_credential(&unknown_issuer).unwrap(),
+            government.issue_credential(&revoked).unwrap(),
+            club.issue_credential(&gold).unwrap(),
+            government.issue_credential(&adult).unwrap(),
+        ];
+        for (credential, signature) in [&young, &unknown_issuer, &revoked, &gold, &adult]
+            .into_iter()
+            .zip(signatures)
+        {
+            wallet.insert(credential.clone(), signature).unwrap();
+        }
+        government.revoke(&revoked).unwrap();
+
+        // 다른 Holder의 credential은 보관할 수 없음
+        let mut others = adult.clone();
+        others.holder_public_key = HolderKey::new(F::from(7u64)).public_key;
+        let others_signature = government.issue_credential(&others).unwrap();
+        assert!(matches!(
+            wallet.insert(others, others_signature),
+            Err(Error::HolderKeyMismatch)
+        ));
+
+        // 파일로 저장한 wallet을 다시 읽으면 같은 키와 credential
+        let path = std::env::temp_dir().join(format!("wallet-{}.json", rand::random::<u64>()));
+        wallet.save(&path).unwrap();
+        // 비밀 키가 들어 있으므로 소유자만 읽을 수 있음
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let loaded = Wallet::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.key(), wallet.key());
+        assert_eq!(loaded.credentials().len(), 5);
+        for (loaded, stored) in loaded.credentials().iter().zip(wallet.credentials()) {
+            assert_eq!(
+                PoseidonHasher::hash(&loaded.credential),
+                PoseidonHasher::hash(&stored.credential)
+            );
+            assert_eq!(loaded.signature, stored.signature);
+        }
+        assert!(matches!(Wallet::load(&path), Err(Error::Io(_))));
+
+        // 속성 값이 schema에 맞지 않는 wallet 파일은 읽을 수 없음
+        let corrupted = |edit: &dyn Fn(&mut Vec<serde_json::Value>)| {
+            let mut json = serde_json::to_value(&wallet).unwrap();
+            let attributes = json["credentials"][0]["credential"]["attributes"]
+                .as_array_mut()
+                .unwrap();
+            edit(attributes);
+            std::fs::write(&path, serde_json::to_vec(&json).unwrap()).unwrap();
+            let loaded = Wallet::load(&path);
+            std::fs::remove_file(&path).unwrap();
+            loaded
+        };
+        let type_mismatch = corrupted(&|attributes| {
+            attributes[0] = serde_json::to_value(Value::Integer(2000)).unwrap();
+        });
+        assert!(matches!(type_mismatch, Err(Error::Serialization(_))));
+        let missing = corrupted(&|attributes| {
+            attributes.pop();
+        });
+        assert!(matches!(missing, Err(Error::Serialization(_))));
+
+        // 나이 미달, 신뢰하지 않는 Issuer, 폐기된 credential은 건너뜀
+        let request = PresentationRequest {
+            verifier_id: string_to_bytes("2").unwrap(),
+            challenge: test_challenge(),
+            today: test_today(),
+            tree_height: TEST_TREE_HEIGHT,
+            schema: person_schema(),
+            predicate: Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+            disclosure: Disclosure::none().with_attribute(HOLDER_NAME),
+            encoding: CredentialEncoding::Bytes,
+            issuers: IssuerSet::new(vec![
+                government.trusted_issuer().unwrap(),
+                club.trusted_issuer().unwrap(),
+            ])
+            .unwrap(),
+            reveal_issuer: false,
+        };
+        let revocations = RevocationLists::from([
+            (government.id, government.revoked_indices()),
+            (club.id, club.revoked_indices()),
+            (untrusted.id, untrusted.revoked_indices()),
+        ]);
+        let found = loaded.find(&request, &revocations).unwrap();
+        assert_eq!(found.signature.serial, 2);
+        assert_eq!(
+            found.credential.attribute(HOLDER_NAME),
+            Some(&Value::String("José Álvarez".to_string()))
+        );
+        let circuit = loaded
+            .circuit::<PoseidonHasher>(&request, &revocations)
+            .unwrap();
+        assert_eq!(circuit.scope, Verifier::new("2").unwrap().scope());
+        assert!(Holder::preflight(circuit).is_ok());
+
+        // Issuer의 폐기 리스트를 모르면 그 Issuer의 credential은 사용하지 않음
+        let without_government = RevocationLists::from([(club.id, vec![])]);
+        assert!(matches!(
+            loaded.circuit::<PoseidonHasher>(&request, &without_government),
+            Err(Error::NoMatchingCredential)
+        ));
+
+        // 같은 wallet에서 요청의 schema와 조건식에 맞는 다른 Issuer의 credential을 고름
+        let level_request = PresentationRequest {
+            schema: membership_schema(),
+            predicate: Predicate::compare(
+                Attribute::named("level"),
+                CmpOp::Eq,
+                Value::Enum("gold".to_string()),
+            ),
+            disclosure: Disclosure::none(),
+            ..request.clone()
+        };
+        let circuit = loaded
+            .circuit::<PoseidonHasher>(&level_request, &revocations)
+            .unwrap();
+        assert_eq!(circuit.credential.issuer_id, club.id);
+        assert!(Holder::preflight(circuit).is_ok());
     }
 
-    // Issuer의 credential 발급. credentials를 해시하여 publish
-    for cred in &credentials {
-        issuer.issue_credential(cred).unwrap();
+    #[test]
+    // 증명 요청과 presentation을 JSON으로 주고받고 Verifier가 public input을 다시 만들어 검증
+    fn test_presentation() {
+        let (government, adult, signature) =
+            signed_person_credential(Date::new(2002, 1, 1).unwrap());
+        let other = Issuer::<PoseidonHasher>::new("3", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        let mut wallet = Wallet::new(test_holder_key());
+        wallet.insert(adult.clone(), signature).unwrap();
+
+        let mut verifier = Verifier::new("2").unwrap();
+        let request = PresentationRequest {
+            verifier_id: verifier.id,
+            challenge: verifier.new_challenge(),
+            today: test_today(),
+            tree_height: TEST_TREE_HEIGHT,
+            schema: person_schema(),
+            predicate: Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+            disclosure: Disclosure::none().with_attribute(HOLDER_NAME),
+            encoding: CredentialEncoding::Bytes,
+            issuers: IssuerSet::new(vec![
+                government.trusted_issuer().unwrap(),
+                other.trusted_issuer().unwrap(),
+            ])
+            .unwrap(),
+            reveal_issuer: true,
+        };
+        let (proving_key, verifying_key) =
+            verifier.setup_request::<PoseidonHasher>(&request).unwrap();
+        let revocations = RevocationLists::from([
+            (government.id, government.revoked_indices()),
+            (other.id, other.revoked_indices()),
+        ]);
+
+        // 요청과 presentation은 JSON으로 주고받음
+        let request: PresentationRequest =
+            serde_json::from_str(&serde_json::to_string(&request).unwrap()).unwrap();
+        let presentation =
+            Holder::present::<PoseidonHasher>(&wallet, proving_key.clone(), &request, &revocations)
+                .unwrap();
+        let presentation: Presentation =
+            serde_json::from_str(&serde_json::to_string(&presentation).unwrap()).unwrap();
+        assert_eq!(presentation.revealed_issuer, Some(government.id));
+        assert_eq!(
+            presentation.nullifier(),
+            Some(nullifier(test_holder_key().secret_key(), verifier.scope()))
+        );
+        assert_eq!(
+            presentation.challenge_binding(),
+            Some(challenge_binding(
+                test_holder_key().secret_key(),
+                &adult,
+                request.challenge
+            ))
+        );
+
+        // 공개한 값을 바꾸면 Verifier가 다시 만든 public input과 다름
+        let mut renamed = presentation.clone();
+        renamed.disclosed.attributes[0].1 = Value::String("Bob".to_string());
+        assert!(matches!(
+            verifier.check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &request,
+                &renamed,
+                &revocations
+            ),
+            Err(Error::PresentationMismatch)
+        ));
+        let mut hidden_issuer = presentation.clone();
+        hidden_issuer.revealed_issuer = None;
+        assert!(matches!(
+            verifier.check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &request,
+                &hidden_issuer,
+                &revocations
+            ),
+            Err(Error::PresentationMismatch)
+        ));
+
+        // Holder가 계산한 값을 바꾸면 증명이 검증되지 않음
+        let mut forged = presentation.clone();
+        let binding_index = forged.public_inputs.len() - 2;
+        forged.public_inputs[binding_index] += F::from(1u64);
+        assert!(matches!(
+            verifier.check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &request,
+                &forged,
+                &revocations
+            ),
+            Err(Error::InvalidProof)
+        ));
+
+        // 증명 후 Issuer가 다른 credential을 폐기하면 presentation의 revocation root는 현재 root가 아님
+        let mut revoked_later = revocations.clone();
+        revoked_later.insert(government.id, vec![3]);
+        assert!(matches!(
+            verifier.check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &request,
+                &presentation,
+                &revoked_later
+            ),
+            Err(Error::UnknownRevocationRoot)
+        ));
+
+        verifier
+            .check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &request,
+                &presentation,
+                &revocations,
+            )
+            .unwrap();
+        // 같은 presentation을 다시 제출할 수 없음
+        assert!(matches!(
+            verifier.check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &request,
+                &presentation,
+                &revocations
+            ),
+            Err(Error::UnknownChallenge)
+        ));
+
+        // 새 요청에 이전 presentation을 제출하면 challenge가 다름
+        let next_request = PresentationRequest {
+            challenge: verifier.new_challenge(),
+            ..request.clone()
+        };
+        assert!(matches!(
+            verifier.check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &next_request,
+                &presentation,
+                &revocations
+            ),
+            Err(Error::PresentationMismatch)
+        ));
+        // 새 요청에 답한 증명도 같은 Holder의 것이면 nullifier가 같음
+        let next =
+            Holder::present::<PoseidonHasher>(&wallet, proving_key, &next_request, &revocations)
+                .unwrap();
+        assert!(matches!(
+            verifier.check_presentation::<PoseidonHasher>(
+                &verifying_key,
+                &next_request,
+                &next,
+                &revocations
+            ),
+            Err(Error::NullifierReused)
+        ));
     }
 
-    // Issuer가 publish한 해시된 credentials
-    let hashed_creds: [Sha256Digest; MAX_CREDENTIALS] = issuer
-        .hashed_credentials()
-        .unwrap()
-        .try_into()
-        .expect("Wrong length");
+    #[test]
+    // setup은 호출마다 OS 난수로 다른 키를 만들고, 테스트 전용 setup은 seed가 같으면 같은 키를 만듦
+    fn test_setup() {
+        let (issuer, cred, signature) = signed_person_credential(Date::new(2000, 1, 1).unwrap());
+
+        let verifier = Verifier::new("2").unwrap();
+        let shape = ClaimShape {
+            mode: IssuanceMode::Signed,
+            schema: person_schema(),
+            predicate: Predicate::age_at_least(HOLDER_DOB, MIN_AGE),
+            disclosure: Disclosure::none(),
+            encoding: CredentialEncoding::Bytes,
+        };
+        let setup = || {
+            verifier
+                .setup::<PoseidonHasher>(
+                    TEST_TREE_HEIGHT,
+                    shape.mode,
+                    &shape.schema,
+                    &shape.predicate,
+                    &shape.disclosure,
+                    shape.encoding,
+                )
+                .unwrap()
+        };
+        let (proving_key, verifying_key) = setup();
+        let (_, other_verifying_key) = setup();
+        assert_ne!(verifying_key, other_verifying_key);
+
+        let seeded = |seed| {
+            verifier
+                .setup_deterministic::<PoseidonHasher>(TEST_TREE_HEIGHT, &shape, seed)
+                .unwrap()
+                .1
+        };
+        assert_eq!(seeded(1), seeded(1));
+        assert_ne!(seeded(1), seeded(2));
+
+        // 증명은 같은 setup의 검증 키로만 검증됨
+        let circuit = AgeCircuit {
+            predicate: shape.predicate.clone(),
+            scope: verifier.scope(),
+            ..test_age_circuit::<PoseidonHasher>(
+                cred,
+                Issuance::Signed {
+                    issuer_public_key: issuer.public_key(),
+                    signature: signature.clone(),
+                },
+                non_revocation_path(TEST_TREE_HEIGHT, &[], signature.serial as usize).unwrap(),
+            )
+        };
+        let public_inputs = circuit.public_inputs().unwrap();
+        let proof = Holder::prove_checked(proving_key, circuit).unwrap();
+        assert!(Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof).unwrap());
+        assert!(!Groth16::<Bn254>::verify(&other_verifying_key, &public_inputs, &proof).unwrap());
+    }
 
-    // ------------------------------ Verifier ------------------------------
+    #[test]
+    // 만 19세 이상 65세 이하이고 이름이 허용된 목록에 있는지 한 번에 증명
+    // circuit의 판단은 native 계산과 같아야 하고, 조건식의 값은 public input으로 할당
+    fn test_predicate() {
+        let date = |s: &str| s.parse::<Date>().unwrap();
+        let today = test_today();
+        let predicate = Predicate::in_range(
+            Attribute::age(HOLDER_DOB),
+            Value::Integer(19),
+            Value::Integer(65),
+        )
+        .and(Predicate::in_set(
+            Attribute::named(HOLDER_NAME),
+            vec![
+                Value::String("Alice".to_string()),
+                Value::String("김민수".to_string()),
+            ],
+        ));
+
+        let credential = |holder_name: &str, holder_dob: &str| {
+            person_credential(
+                string_to_bytes("1").unwrap(),
+                test_holder_key().public_key,
+                test_validity(),
+                holder_name.to_string(),
+                date(holder_dob),
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap()
+        };
+        for (cred, expected) in [
+            (credential("Alice", "2006-06-15"), true),  // 만 19세
+            (credential("김민수", "1959-06-16"), true), // 만 65세 (66번째 생일 전날)
+            (credential("Bob", "2000-01-01"), false),   // 목록에 없는 이름
+            (credential("Alice", "2006-06-16"), false), // 만 18세
+            (credential("Alice", "1959-06-15"), false), // 만 66세
+        ] {
+            assert_eq!(predicate.evaluate(&cred, today), Ok(expected));
+            assert_eq!(satisfies(&cred, today, predicate.clone()), expected);
+        }
 
-    // verifier의 circuit setup
-    let verifier = Verifier::new("2");
-    let (proving_key, verifying_key) = verifier.setup().unwrap();
-
-    let public_inputs = {
-        let mut public_inputs = Vec::new();
-
-        // circuit의 generate_constraints에서와 "동일한 순서"로 public input 제공
-        let cutoff_year = <<Bn254 as Pairing>::ScalarField>::from_str(CUTOFF_YEAR).unwrap();
-        public_inputs.push(cutoff_year);
-
-        // hashed_credentials의 각 credential의 바이트를 비트 단위로 field 원소로 변환하여 public input으로 사용
-        for cred in &hashed_creds {
-            let cred_bytes: &[u8] = cred;
-            for byte in cred_bytes.iter() {
-                for i in 0..8 {
-                    // Little-endian
-                    if (byte >> i) & 1 == 1 {
-                        public_inputs.push(F::one());
-                    } else {
-                        public_inputs.push(F::zero());
-                    }
-                }
+        // OR: 2000년 이전 출생이거나 이름이 "Bob"
+        let predicate = Predicate::compare(
+            Attribute::named(HOLDER_DOB),
+            CmpOp::Le,
+            Value::Date(date("1999-12-31")),
+        )
+        .or(Predicate::compare(
+            Attribute::named(HOLDER_NAME),
+            CmpOp::Eq,
+            Value::String("Bob".to_string()),
+        ));
+        assert!(satisfies(
+            &credential("Alice", "1999-12-31"),
+            today,
+            predicate.clone()
+        ));
+        assert!(satisfies(
+            &credential("Bob", "2000-01-01"),
+            today,
+            predicate.clone()
+        ));
+        assert!(!satisfies(
+            &credential("Alice", "2000-01-01"),
+            today,
+            predicate.clone()
+        ));
+
+        // 조건식의 public input은 값이 나오는 순서대로 나열
+        assert_eq!(
+            predicate.public_inputs(&person_schema()).unwrap(),
+            vec![
+                date("1999-12-31").to_field(),
+                string_to_field("Bob").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    // 속성과 값의 타입이 맞지 않거나 문자열에 대소 비교를 하면 setup 전에 거부
+    fn test_predicate_type_errors() {
+        let mismatch = Predicate::compare(
+            Attribute::age(HOLDER_DOB),
+            CmpOp::Ge,
+            Value::Date(Date::new(2000, 1, 1).unwrap()),
+        );
+        let unordered = Predicate::age_at_least(HOLDER_DOB, MIN_AGE).or(Predicate::in_range(
+            Attribute::named(HOLDER_NAME),
+            Value::String("1".to_string()),
+            Value::String("2".to_string()),
+        ));
+        assert_eq!(
+            mismatch.check(&person_schema()),
+            Err(PredicateError::TypeMismatch)
+        );
+        assert_eq!(
+            unordered.check(&person_schema()),
+            Err(PredicateError::UnorderedAttribute)
+        );
+        // schema에 없는 속성, 날짜가 아닌 속성의 만 나이
+        assert_eq!(
+            Predicate::age_at_least("height", MIN_AGE).check(&person_schema()),
+            Err(PredicateError::UnknownAttribute)
+        );
+        assert_eq!(
+            Predicate::age_at_least(HOLDER_NAME, MIN_AGE).check(&person_schema()),
+            Err(PredicateError::TypeMismatch)
+        );
+
+        let cred = person_credential(
+            string_to_bytes("1").unwrap(),
+            test_holder_key().public_key,
+            test_validity(),
+            "Alice".to_string(),
+            Date::new(2000, 1, 1).unwrap(),
+            F::zero(),
+        )
+        .unwrap();
+        assert_eq!(
+            mismatch.evaluate(&cred, test_today()),
+            Err(PredicateError::TypeMismatch)
+        );
+        assert!(matches!(
+            Verifier::new("2").unwrap().setup::<PoseidonHasher>(
+                TEST_TREE_HEIGHT,
+                IssuanceMode::Published(DigestEncoding::Packed),
+                &person_schema(),
+                &unordered,
+                &Disclosure::none(),
+                CredentialEncoding::Bytes,
+            ),
+            Err(Error::Predicate(PredicateError::UnorderedAttribute))
+        ));
+    }
+
+    #[test]
+    // 잘못된 입력은 panic 대신 원인을 담은 오류로 반환
+    fn test_error_reporting() {
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        for i in 0..tree_capacity(TEST_TREE_HEIGHT) {
+            let cred = person_credential(
+                issuer.id,
+                test_holder_key().public_key,
+                test_validity(),
+                "Alice".to_string(),
+                Date::UNIX_EPOCH,
+                F::from(i as u64),
+            )
+            .unwrap();
+            issuer.issue_credential(&cred).unwrap();
+        }
+        let cred = person_credential(
+            issuer.id,
+            test_holder_key().public_key,
+            test_validity(),
+            "Bob".to_string(),
+            Date::UNIX_EPOCH,
+            F::zero(),
+        )
+        .unwrap();
+        let error = issuer.issue_credential(&cred).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::CredentialTreeFull {
+                tree_height: TEST_TREE_HEIGHT,
+                capacity: 4
             }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "credential tree of height 3 is full (4 credentials)"
+        );
+
+        // 발급되지 않은 credential은 Merkle path를 만들 수 없음
+        assert!(matches!(
+            Holder::new(test_holder_key(), cred)
+                .unwrap()
+                .membership_path::<PoseidonHasher>(TEST_TREE_HEIGHT, &issuer.hashed_credentials()),
+            Err(Error::CredentialNotPublished)
+        ));
+
+        // 하위 오류는 원인과 함께 변환
+        let error: Error = "2025-13-01".parse::<Date>().unwrap_err().into();
+        assert_eq!(error.to_string(), "invalid date: month is not in 1..=12");
+        let error = Verifier::new(&"v".repeat(MAX_STRING_BYTES + 1))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("longer than 31 UTF-8 bytes"));
+    }
+
+    #[test]
+    // 임의의 UTF-8 문자열을 field 원소 하나로 인코딩. 길이 제한을 넘으면 오류
+    fn test_string_encoding() {
+        // 길이를 함께 기록하므로 끝의 0 바이트도 구분
+        assert_ne!(
+            string_to_bytes("a").unwrap(),
+            string_to_bytes("a\0").unwrap()
+        );
+        assert!(string_to_bytes(&"a".repeat(MAX_STRING_BYTES)).is_ok());
+        assert_eq!(
+            string_to_bytes(&"a".repeat(MAX_STRING_BYTES + 1)),
+            Err(StringError::TooLong)
+        );
+        // 길이 제한은 문자 수가 아니라 UTF-8 바이트 수 (한글은 3바이트)
+        assert!(string_to_bytes(&"가".repeat(10)).is_ok());
+        assert_eq!(string_to_bytes(&"가".repeat(11)), Err(StringError::TooLong));
+
+        // 실제 이름으로 발급한 credential도 native 해시와 circuit 안의 해시가 같아야 함
+        for name in ["Alice", "김철수", "José Núñez", "🦀 Ferris"] {
+            let cred = person_credential(
+                string_to_bytes("issuer").unwrap(),
+                test_holder_key().public_key,
+                test_validity(),
+                name.to_string(),
+                Date::new(2000, 1, 1).unwrap(),
+                F::from(rand::random::<u128>()),
+            )
+            .unwrap();
+            for encoding in [CredentialEncoding::Bytes, CredentialEncoding::Canonical] {
+                assert!(native_matches_circuit::<Sha256Hasher>(&cred, 0, encoding));
+                assert!(native_matches_circuit::<PoseidonHasher>(&cred, 0, encoding));
+            }
+            let predicate = Predicate::compare(
+                Attribute::named(HOLDER_NAME),
+                CmpOp::Eq,
+                Value::String(name.to_string()),
+            );
+            assert!(satisfies(&cred, test_today(), predicate));
         }
-        public_inputs
-    };
 
-    // ------------------------------ Holder ------------------------------
-    // 2005년생 holder
-    let holder_2005 = Holder::new("2005", credentials[0].clone());
+        // 길이 제한을 넘는 credential 속성 / 조건식의 값 / id
+        let long_name = "Pablo Diego José Francisco de Paula".to_string();
+        assert_eq!(
+            Credential::new(
+                [0u8; 32],
+                test_holder_key().public_key,
+                test_validity(),
+                &person_schema(),
+                vec![
+                    (HOLDER_NAME, Value::String(long_name.clone())),
+                    (HOLDER_DOB, Value::Date(Date::UNIX_EPOCH)),
+                ],
+                F::zero(),
+            )
+            .unwrap_err(),
+            SchemaError::StringTooLong
+        );
+        assert_eq!(
+            Predicate::compare(
+                Attribute::named(HOLDER_NAME),
+                CmpOp::Eq,
+                Value::String(long_name.clone())
+            )
+            .check(&person_schema()),
+            Err(PredicateError::StringTooLong)
+        );
+        assert!(matches!(
+            Issuer::<PoseidonHasher>::new(&long_name, TEST_TREE_HEIGHT, person_schema()),
+            Err(Error::InvalidId { .. })
+        ));
+    }
 
-    let age_circuit_2005 = AgeCircuit {
-        dob_cutoff_year: CUTOFF_YEAR.to_string(),
-        hashed_credentials: hashed_creds.clone(),
-        credential: holder_2005.credentials.clone(),
-    };
+    // 속성을 최대 개수만큼 선언한 schema
+    fn membership_schema() -> Schema {
+        Schema::new(vec![
+            (HOLDER_NAME, AttributeType::String),
+            (HOLDER_DOB, AttributeType::Date),
+            ("points", AttributeType::Integer),
+            (
+                "level",
+                AttributeType::Enum(vec![
+                    "bronze".to_string(),
+                    "silver".to_string(),
+                    "gold".to_string(),
+                ]),
+            ),
+            ("joined", AttributeType::Date),
+            ("member_id", AttributeType::String),
+        ])
+        .unwrap()
+    }
 
-    // 증명 생성 후 local 에서 검증
-    let proof_2005 = Holder::prove(proving_key.clone(), age_circuit_2005.clone()).unwrap();
+    fn membership_credential(points: u64, level: &str) -> Credential {
+        Credential::new(
+            string_to_bytes("1").unwrap(),
+            test_holder_key().public_key,
+            test_validity(),
+            &membership_schema(),
+            vec![
+                ("level", Value::Enum(level.to_string())),
+                (HOLDER_NAME, Value::String("Alice".to_string())),
+                (HOLDER_DOB, Value::Date(Date::new(2000, 1, 1).unwrap())),
+                ("points", Value::Integer(points)),
+                ("joined", Value::Date(Date::new(2020, 3, 1).unwrap())),
+                ("member_id", Value::String("42".to_string())),
+            ],
+            F::from(rand::random::<u128>()),
+        )
+        .unwrap()
+    }
 
-    let is_valid_2005 =
-        Groth16::<Bn254>::verify(&verifying_key, &public_inputs, &proof_2005).unwrap();
-    assert!(is_valid_2005);
+    #[test]
+    // schema에 맞지 않는 schema 선언 / credential은 거부
+    fn test_schema_validation() {
+        let enum_type = || AttributeType::Enum(vec!["a".to_string()]);
+        assert_eq!(
+            Schema::new(vec![
+                ("a", AttributeType::Integer),
+                ("a", AttributeType::Date)
+            ]),
+            Err(SchemaError::DuplicateAttribute)
+        );
+        assert_eq!(
+            Schema::new(vec![("a", AttributeType::Enum(vec![]))]),
+            Err(SchemaError::EmptyEnum)
+        );
+        let names = ["a", "b", "c", "d", "e", "f", "g"];
+        assert_eq!(
+            Schema::new(names.iter().map(|name| (*name, enum_type())).collect()),
+            Err(SchemaError::TooManyAttributes)
+        );
+
+        let schema = person_schema();
+        let dob = || (HOLDER_DOB, Value::Date(Date::new(2000, 1, 1).unwrap()));
+        let name = || (HOLDER_NAME, Value::String("Alice".to_string()));
+        let credential = |attributes| {
+            Credential::new(
+                [0u8; 32],
+                test_holder_key().public_key,
+                test_validity(),
+                &schema,
+                attributes,
+                F::zero(),
+            )
+        };
+        assert!(credential(vec![dob(), name()]).is_ok());
+        assert_eq!(
+            credential(vec![name()]).unwrap_err(),
+            SchemaError::MissingAttribute
+        );
+        assert_eq!(
+            credential(vec![name(), dob(), ("height", Value::Integer(180))]).unwrap_err(),
+            SchemaError::UnknownAttribute
+        );
+        assert_eq!(
+            credential(vec![name(), name(), dob()]).unwrap_err(),
+            SchemaError::DuplicateAttribute
+        );
+        assert_eq!(
+            credential(vec![name(), (HOLDER_DOB, Value::Integer(20000101))]).unwrap_err(),
+            SchemaError::TypeMismatch
+        );
+
+        // enum 속성은 선언한 값만 허용
+        assert!(
+            membership_credential(0, "gold")
+                .attribute("level")
+                .is_some()
+        );
+        assert_eq!(
+            Credential::new(
+                [0u8; 32],
+                test_holder_key().public_key,
+                test_validity(),
+                &Schema::new(vec![("level", enum_type())]).unwrap(),
+                vec![("level", Value::Enum("b".to_string()))],
+                F::zero(),
+            )
+            .unwrap_err(),
+            SchemaError::UnknownVariant
+        );
+
+        // Issuer는 자신의 schema로 만든 credential만 발급
+        let mut issuer =
+            Issuer::<PoseidonHasher>::new("1", TEST_TREE_HEIGHT, person_schema()).unwrap();
+        assert!(matches!(
+            issuer.issue_credential(&membership_credential(0, "gold")),
+            Err(Error::SchemaMismatch)
+        ));
+    }
 
-    // ------------------------------ Verifier ------------------------------
-    // for solidity verifier contract
-    let vk_solidity = verifying_key.to_solidity();
-    println!("Verifying Key for Solidity: {:?}", vk_solidity);
+    #[test]
+    // 속성이 많은 schema도 모든 해시 방식에서 native 해시와 circuit 안의 해시가 같아야 함
+    // 정수 / enum 속성에 대한 조건식도 circuit에서 검사
+    fn test_custom_schema() {
+        let credential = membership_credential(150, "silver");
+        for encoding in [CredentialEncoding::Bytes, CredentialEncoding::Canonical] {
+            assert!(native_matches_circuit::<Sha256Hasher>(
+                &credential,
+                1,
+                encoding
+            ));
+            assert!(native_matches_circuit::<PoseidonHasher>(
+                &credential,
+                1,
+                encoding
+            ));
+            assert!(native_matches_circuit::<PedersenHasher>(
+                &credential,
+                1,
+                encoding
+            ));
+        }
 
-    let proof_solidity = proof_2005.to_solidity();
-    println!("Proof for Solidity: {:?}", proof_solidity);
+        let predicate =
+            Predicate::compare(Attribute::named("points"), CmpOp::Ge, Value::Integer(100))
+                .and(Predicate::in_set(
+                    Attribute::named("level"),
+                    vec![
+                        Value::Enum("silver".to_string()),
+                        Value::Enum("gold".to_string()),
+                    ],
+                ))
+                .and(Predicate::age_at_least(HOLDER_DOB, MIN_AGE));
+
+        for (credential, expected) in [
+            (membership_credential(150, "silver"), true),
+            (membership_credential(99, "gold"), false),
+            (membership_credential(100, "bronze"), false),
+        ] {
+            assert_eq!(predicate.evaluate(&credential, test_today()), Ok(expected));
+            assert_eq!(
+                satisfies(&credential, test_today(), predicate.clone()),
+                expected
+            );
+        }
 
-    let public_inputs_solidity: Vec<String> = public_inputs.to_solidity();
-    println!("Public Inputs for Solidity: {:?}", public_inputs_solidity);
+        // enum 값은 선언한 index로 public input이 됨
+        assert_eq!(
+            predicate.public_inputs(&membership_schema()).unwrap(),
+            vec![
+                F::from(100u64),
+                F::from(1u64),
+                F::from(2u64),
+                F::from(MIN_AGE)
+            ]
+        );
+        // enum 속성에는 대소 비교 불가
+        assert_eq!(
+            Predicate::compare(
+                Attribute::named("level"),
+                CmpOp::Ge,
+                Value::Enum("silver".to_string())
+            )
+            .check(&membership_schema()),
+            Err(PredicateError::UnorderedAttribute)
+        );
+
+        // 타입 구성이 같은 다른 schema의 credential은 해시가 달라 person schema의 credential로 쓸 수 없음
+        let club_schema = Schema::new(vec![
+            ("club", AttributeType::String),
+            ("joined", AttributeType::Date),
+        ])
+        .unwrap();
+        assert_ne!(club_schema.id(), person_schema().id());
+        let joined = Date::new(2000, 1, 1).unwrap();
+        let club_credential = Credential::new(
+            [0u8; 32],
+            test_holder_key().public_key,
+            test_validity(),
+            &club_schema,
+            vec![
+                ("club", Value::String("chess".to_string())),
+                ("joined", Value::Date(joined)),
+            ],
+            F::from(7u64),
+        )
+        .unwrap();
+        let relabeled = person_credential(
+            [0u8; 32],
+            test_holder_key().public_key,
+            test_validity(),
+            "chess".to_string(),
+            joined,
+            F::from(7u64),
+        )
+        .unwrap();
+        assert_ne!(
+            Sha256Hasher::hash(&club_credential),
+            Sha256Hasher::hash(&relabeled)
+        );
+        assert_ne!(
+            PoseidonHasher::hash(&club_credential),
+            PoseidonHasher::hash(&relabeled)
+        );
+        assert_ne!(
+            PedersenHasher::hash(&club_credential),
+            PedersenHasher::hash(&relabeled)
+        );
+
+        // 다른 schema로 서명받은 credential로는 증명할 수 없음
+        let mut club = Issuer::<PoseidonHasher>::new("3", TEST_TREE_HEIGHT, club_schema).unwrap();
+        let signature = club.issue_credential(&club_credential).unwrap();
+        let circuit = test_age_circuit::<PoseidonHasher>(
+            relabeled,
+            Issuance::Signed {
+                issuer_public_key: club.public_key(),
+                signature: signature.clone(),
+            },
+            non_revocation_path(TEST_TREE_HEIGHT, &[], signature.serial as usize).unwrap(),
+        );
+        assert!(matches!(
+            Holder::preflight(circuit),
+            Err(Error::InvalidSignature)
+        ));
+    }
 
-    let contract_address = "0x5FbDB2315678afecb367f032d93F642f64180aa3"
-        .parse::<Address>()
+    // property test에서 사용하는 임의의 날짜
+    fn date_strategy() -> impl proptest::strategy::Strategy<Value = Date> {
+        use proptest::prelude::*;
+
+        (1900u32..2100, 1u32..=12, 1u32..=31).prop_map(|(year, month, day)| {
+            // 해당 월에 없는 일은 그 달의 마지막 날로
+            (1..=day)
+                .rev()
+                .find_map(|day| Date::new(year, month, day).ok())
+                .unwrap()
+        })
+    }
+
+    // property test에서 사용하는 임의의 credential
+    // 각 필드는 Issuer / Holder가 실제로 사용하는 것과 같은 10진수 문자열
+    fn credential_strategy() -> impl proptest::strategy::Strategy<Value = Credential> {
+        use proptest::prelude::*;
+
+        // 이름은 UTF-8로 최대 28바이트 (4바이트 문자 7개)
+        (any::<u64>(), "\\PC{0,7}", date_strategy(), any::<u128>()).prop_map(
+            |(issuer_id, holder_name, holder_dob, randomness)| {
+                person_credential(
+                    string_to_bytes(&issuer_id.to_string()).unwrap(),
+                    test_holder_key().public_key,
+                    test_validity(),
+                    holder_name,
+                    holder_dob,
+                    F::from(randomness),
+                )
+                .unwrap()
+            },
+        )
+    }
+
+    // property test에서 사용하는 credential 할당 방식
+    fn encoding_strategy() -> impl proptest::strategy::Strategy<Value = CredentialEncoding> {
+        use proptest::prelude::*;
+        prop_oneof![
+            Just(CredentialEncoding::Bytes),
+            Just(CredentialEncoding::Canonical)
+        ]
+    }
+
+    // native 해시와 circuit 안의 해시가 같은 값을 계산하는지 확인
+    // 1. Issuer가 publish하는 credential 해시 == circuit 안에서 계산한 credential 해시
+    // 2. native로 만든 credential tree의 root == circuit 안에서 Merkle path로 계산한 root
+    fn native_matches_circuit<H: CredentialHasher>(
+        credential: &Credential,
+        index: usize,
+        encoding: CredentialEncoding,
+    ) -> bool {
+        // index번째 leaf가 credential인 tree
+        let mut hashed_creds = vec![H::empty_leaf(); index];
+        hashed_creds.push(H::hash(credential));
+        let root = credential_root::<H>(TEST_TREE_HEIGHT, &hashed_creds).unwrap();
+        let path = membership_path::<H>(TEST_TREE_HEIGHT, &hashed_creds, index).unwrap();
+
+        let cs = ark_relations::r1cs::ConstraintSystem::<F>::new_ref();
+        let credential_var = CredentialVar::new_encoded(
+            cs.clone(),
+            credential.clone(),
+            AllocationMode::Witness,
+            encoding,
+        )
         .unwrap();
+        let leaf_params_var = H::leaf_params_var(cs.clone()).unwrap();
+        let two_to_one_params_var = H::two_to_one_params_var(cs.clone()).unwrap();
+
+        let hash_var = H::hash_var(&leaf_params_var, &credential_var).unwrap();
+        let expected_hash_var =
+            H::DigestVar::new_witness(cs.clone(), || Ok(H::hash(credential))).unwrap();
+        hash_var.enforce_equal(&expected_hash_var).unwrap();
+
+        let path_var = CredentialPathVar::<H>::new_witness(cs.clone(), || Ok(path)).unwrap();
+        let root_var = path_var
+            .calculate_root(
+                &leaf_params_var,
+                &two_to_one_params_var,
+                H::encode_var(&credential_var).unwrap().borrow(),
+            )
+            .unwrap();
+        let expected_root_var = H::DigestVar::new_witness(cs.clone(), || Ok(root)).unwrap();
+        root_var.enforce_equal(&expected_root_var).unwrap();
 
-    send_tx(
-        proof_solidity,
-        public_inputs_solidity,
-        vk_solidity,
-        contract_address,
-    )
-    .await
-    .unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    // 만 나이 / 생년월일에 대한 비교 두 개를 AND 또는 OR로 묶은 조건식
+    fn predicate_strategy() -> impl proptest::strategy::Strategy<Value = Predicate> {
+        use proptest::prelude::*;
+
+        let op = prop_oneof![
+            Just(CmpOp::Ge),
+            Just(CmpOp::Le),
+            Just(CmpOp::Eq),
+            Just(CmpOp::Ne)
+        ];
+        let leaf = prop_oneof![
+            (op.clone(), 0u64..100).prop_map(|(op, age)| {
+                Predicate::compare(Attribute::age(HOLDER_DOB), op, Value::Integer(age))
+            }),
+            (op, date_strategy()).prop_map(|(op, date)| {
+                Predicate::compare(Attribute::named(HOLDER_DOB), op, Value::Date(date))
+            }),
+            (0u64..100, 0u64..100).prop_map(|(a, b)| {
+                Predicate::in_range(
+                    Attribute::age(HOLDER_DOB),
+                    Value::Integer(a.min(b)),
+                    Value::Integer(a.max(b)),
+                )
+            }),
+        ];
+        (leaf.clone(), leaf, any::<bool>())
+            .prop_map(|(a, b, and)| if and { a.and(b) } else { a.or(b) })
+    }
+
+    proptest::proptest! {
+        #![proptest_config(proptest::prelude::ProptestConfig::with_cases(16))]
+
+        #[test]
+        // circuit의 나이 판단이 native로 계산한 만 나이와 항상 같아야 함
+        // 기준 날짜는 min_age번째 생일 전후 1년 안에서 선택
+        fn prop_age_circuit_matches_native(
+            holder_dob in date_strategy(),
+            min_age in 0u32..100,
+            year_offset in 0u32..=2,
+            today in date_strategy(),
+        ) {
+            let today = Date::new(
+                holder_dob.year() + min_age + year_offset - 1,
+                today.month(),
+                today.day().min(28),
+            )
+            .unwrap();
+            proptest::prop_assume!(holder_dob <= today);
+            proptest::prop_assert_eq!(
+                is_old_enough(holder_dob, today, min_age),
+                holder_dob.age_on(today) >= min_age
+            );
+        }
+
+        #[test]
+        // circuit이 만족되는지가 native로 계산한 조건식의 결과와 항상 같아야 함
+        fn prop_predicate_circuit_matches_native(
+            credential in credential_strategy(),
+            predicate in predicate_strategy(),
+        ) {
+            proptest::prop_assert_eq!(
+                satisfies(&credential, test_today(), predicate.clone()),
+                predicate.evaluate(&credential, test_today()).unwrap()
+            );
+        }
+
+        #[test]
+        fn prop_sha256_native_matches_circuit(
+            credential in credential_strategy(),
+            index in 0..tree_capacity(TEST_TREE_HEIGHT),
+            encoding in encoding_strategy(),
+        ) {
+            proptest::prop_assert!(native_matches_circuit::<Sha256Hasher>(&credential, index, encoding));
+        }
+
+        #[test]
+        fn prop_poseidon_native_matches_circuit(
+            credential in credential_strategy(),
+            index in 0..tree_capacity(TEST_TREE_HEIGHT),
+            encoding in encoding_strategy(),
+        ) {
+            proptest::prop_assert!(native_matches_circuit::<PoseidonHasher>(&credential, index, encoding));
+        }
+
+        #[test]
+        fn prop_pedersen_native_matches_circuit(
+            credential in credential_strategy(),
+            index in 0..tree_capacity(TEST_TREE_HEIGHT),
+            encoding in encoding_strategy(),
+        ) {
+            proptest::prop_assert!(native_matches_circuit::<PedersenHasher>(&credential, index, encoding));
+        }
+    }
 }
@@ -1,2 +1,5 @@
+pub mod poseidon;
+pub mod serialization;
 pub mod solidity;
+#[allow(clippy::module_inception)]
 pub mod utils;
@@ -0,0 +1,32 @@
+use ark_crypto_primitives::sponge::poseidon::{PoseidonConfig, find_poseidon_ark_and_mds};
+use ark_ff::PrimeField;
+
+use crate::F;
+
+// BN254 scalar field에서 흔히 쓰이는 Poseidon 파라미터 (width 3, x^5 S-box)
+const POSEIDON_RATE: usize = 2;
+const POSEIDON_CAPACITY: usize = 1;
+const POSEIDON_FULL_ROUNDS: usize = 8;
+const POSEIDON_PARTIAL_ROUNDS: usize = 57;
+const POSEIDON_ALPHA: u64 = 5;
+
+/// credential commitment와 credential tree에서 사용하는 Poseidon 파라미터
+/// round constant와 MDS 행렬은 Grain LFSR로 결정적으로 생성되므로 누구나 같은 값을 얻음
+pub fn poseidon_config() -> PoseidonConfig<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        POSEIDON_RATE,
+        POSEIDON_FULL_ROUNDS as u64,
+        POSEIDON_PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonConfig::new(
+        POSEIDON_FULL_ROUNDS,
+        POSEIDON_PARTIAL_ROUNDS,
+        POSEIDON_ALPHA,
+        mds,
+        ark,
+        POSEIDON_RATE,
+        POSEIDON_CAPACITY,
+    )
+}
@@ -0,0 +1,27 @@
+//! serde로 저장하거나 전송하는 값 중 arkworks 타입(field 원소, 곡선 위의 점, Groth16 증명)의 직렬화
+//! arkworks 타입은 serde를 구현하지 않으므로 `#[serde(with = "canonical")]`로 지정
+
+/// `CanonicalSerialize`의 compressed 바이트를 hex 문자열로 직렬화
+pub mod canonical {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use serde::{Deserialize, Deserializer, Serializer, de};
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::new();
+        value
+            .serialize_compressed(&mut bytes)
+            .map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    // 곡선 위의 점은 곡선과 부분군에 속하는지 확인
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let bytes = hex::decode(String::deserialize(deserializer)?).map_err(de::Error::custom)?;
+        T::deserialize_compressed(&bytes[..]).map_err(de::Error::custom)
+    }
+}
@@ -1,21 +1,66 @@
 use ark_bn254::Fr;
+use ark_crypto_primitives::crh::sha256::constraints::DigestVar;
 use ark_ff::BigInteger;
 use ark_ff::PrimeField;
-use ark_r1cs_std::{ToBytesGadget, uint8::UInt8};
-use ark_relations::r1cs::Namespace;
-use std::str::FromStr;
+use ark_r1cs_std::{
+    ToBitsGadget, alloc::AllocVar, eq::EqGadget, fields::fp::FpVar, prelude::Boolean, uint8::UInt8,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
 
-use crate::F;
+use crate::{F, Sha256Digest};
+
+/// packed 모드에서 하나의 field 원소에 담는 digest의 바이트 수 (128비트)
+pub const PACKED_DIGEST_CHUNK_BYTES: usize = 16;
+
+/// SHA256 digest를 public input으로 제공하는 방식
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestEncoding {
+    Bits,   // 비트마다 하나의 field 원소 (256개)
+    Packed, // 128비트씩 묶어 field 원소 2개
+}
 
 /// &[u8]을 Uint8 witness vector로 변환
-pub fn to_byte_vars(cs: impl Into<Namespace<F>>, data: &[u8]) -> Vec<UInt8<F>> {
+pub fn to_byte_vars(
+    cs: impl Into<Namespace<F>>,
+    data: &[u8],
+) -> Result<Vec<UInt8<F>>, SynthesisError> {
     let cs = cs.into().cs();
-    UInt8::new_witness_vec(cs, data).unwrap()
+    UInt8::new_witness_vec(cs, data)
+}
+
+/// field 원소 하나에 인코딩할 수 있는 문자열의 최대 UTF-8 바이트 수
+pub const MAX_STRING_BYTES: usize = 31;
+
+/// 문자열을 field 원소 하나에 해당하는 Little-endian [u8; 32]로 인코딩
+/// native(`Credential::to_bytes`)와 circuit 모두 이 값을 사용
+/// 마지막 바이트가 31 이하이므로 인코딩한 값은 항상 field의 modulus보다 작아 field 원소와 1:1 대응
+/// - 0~30번째 바이트: UTF-8 바이트를 앞에서부터 채우고 나머지는 0
+/// - 31번째 바이트: UTF-8 바이트 수 (끝에 0 바이트가 있는 문자열과 구분)
+pub fn string_to_bytes(s: &str) -> Result<[u8; 32], StringError> {
+    let utf8 = s.as_bytes();
+    if utf8.len() > MAX_STRING_BYTES {
+        return Err(StringError::TooLong);
+    }
+
+    let mut bytes = [0u8; 32];
+    bytes[..utf8.len()].copy_from_slice(utf8);
+    bytes[MAX_STRING_BYTES] = utf8.len() as u8;
+    Ok(bytes)
+}
+
+/// `string_to_bytes`로 인코딩한 field 원소
+pub fn string_to_field(s: &str) -> Result<F, StringError> {
+    Ok(F::from_le_bytes_mod_order(&string_to_bytes(s)?))
 }
 
-/// string을 [u8; 32]로 변환
-pub fn string_to_bytes(s: &str) -> [u8; 32] {
-    let f = Fr::from_str(s).unwrap();
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StringError {
+    #[error("string is longer than {MAX_STRING_BYTES} UTF-8 bytes")]
+    TooLong,
+}
+
+/// field 원소를 Little-endian [u8; 32]로 변환
+pub fn field_to_bytes(f: Fr) -> [u8; 32] {
     let f_bigint = f.into_bigint();
     let f_bytes_vec = f_bigint.to_bytes_le();
     let f_bytes_arr: [u8; 32] = f_bytes_vec
@@ -23,3 +68,43 @@ pub fn string_to_bytes(s: &str) -> [u8; 32] {
         .expect("Failed to convert field element bytes to array");
     f_bytes_arr
 }
+
+/// SHA256 digest를 `encoding` 방식의 public input으로 변환
+/// circuit의 `digest_input_var`와 동일한 순서와 endianness를 사용해야 함
+pub fn digest_to_field_elements(digest: &[u8], encoding: DigestEncoding) -> Vec<F> {
+    match encoding {
+        // 각 바이트의 비트를 Little-endian 순서로
+        DigestEncoding::Bits => digest
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| F::from((byte >> i) & 1)))
+            .collect(),
+        // 16바이트씩 Little-endian 정수로 해석
+        DigestEncoding::Packed => digest
+            .chunks(PACKED_DIGEST_CHUNK_BYTES)
+            .map(F::from_le_bytes_mod_order)
+            .collect(),
+    }
+}
+
+/// SHA256 digest를 `encoding` 방식의 public input으로 할당
+/// packed 모드에서는 digest의 바이트를 witness로 할당하고, 이를 묶은 값이 public input과 같도록 강제
+pub fn digest_input_var(
+    cs: impl Into<Namespace<F>>,
+    digest: &Sha256Digest,
+    encoding: DigestEncoding,
+) -> Result<DigestVar<F>, SynthesisError> {
+    let cs = cs.into().cs();
+    match encoding {
+        DigestEncoding::Bits => DigestVar::new_input(cs, || Ok(digest.clone())),
+        DigestEncoding::Packed => {
+            let digest_var = DigestVar::new_witness(cs.clone(), || Ok(digest.clone()))?;
+            let packed = digest_to_field_elements(digest, DigestEncoding::Packed);
+
+            for (chunk_var, packed) in digest_var.0.chunks(PACKED_DIGEST_CHUNK_BYTES).zip(packed) {
+                let packed_var = FpVar::new_input(cs.clone(), || Ok(packed))?;
+                Boolean::le_bits_to_fp_var(&chunk_var.to_bits_le()?)?.enforce_equal(&packed_var)?;
+            }
+            Ok(digest_var)
+        }
+    }
+}
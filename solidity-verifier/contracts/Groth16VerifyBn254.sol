@@ -31,7 +31,7 @@ contract Groth16VerifyBn254 {
         Pairing.G2Point beta2;
         Pairing.G2Point gamma2;
         Pairing.G2Point delta2;
-        Pairing.G1Point[770] public_input;
+        Pairing.G1Point[] public_input; // public input 개수 + 1
     }
 
     struct Proof {
@@ -48,9 +48,14 @@ contract Groth16VerifyBn254 {
 
     function verifyProof(
         uint256[8] memory proof,
-        uint256[769] memory input,
+        uint256[] memory input,
         VerifyingKey memory vk
     ) public {
+        require(
+            input.length + 1 == vk.public_input.length,
+            "verifier-bad-input-length"
+        );
+
         Proof memory _proof;
         _proof.A = Pairing.G1Point(proof[0], proof[1]);
         _proof.B = Pairing.G2Point([proof[2], proof[3]], [proof[4], proof[5]]);
@@ -1575,7 +1575,7 @@ const vkStruct = {
     X: [vk[10], vk[11]],
     Y: [vk[12], vk[13]],
   },
-  public_input: Array.from({ length: 770 }, (_, i) => ({
+  public_input: Array.from({ length: (vk.length - 14) / 2 }, (_, i) => ({
     X: vk[14 + i * 2],
     Y: vk[15 + i * 2],
   })),